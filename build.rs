@@ -0,0 +1,9 @@
+// Compiles the SCIP protobuf schema (`proto/scip.proto`) into Rust types at build time,
+// consumed by `crate::scip`.
+
+fn main() {
+    println!("cargo:rerun-if-changed=proto/scip.proto");
+
+    prost_build::compile_protos(&["proto/scip.proto"], &["proto/"])
+        .expect("SCIP protobuf schema should always compile successfully");
+}
@@ -0,0 +1,52 @@
+//! A process-wide registry of broadcast channels, keyed by database path, which lets a
+//! [`crate::indexer::Indexer`] notify a [`crate::resolver::Resolver`] connected to the same
+//! database that a file's symbols have changed - without either having a direct reference to
+//! the other.
+//!
+//! This is what backs [`crate::resolver::StreamMode::Subscribe`]: an indexer publishes a
+//! [`Change`] whenever it finishes indexing or de-indexing a file, and a subscribing query
+//! re-scores that file's symbols against its own query, live.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use tokio::sync::broadcast;
+
+/// The number of not-yet-received changes a lagging subscriber can buffer before older ones
+/// are dropped (reported to the subscriber as [`broadcast::error::RecvError::Lagged`]).
+const CHANGE_FEED_CAPACITY: usize = 1024;
+
+/// A file whose symbols were changed by an indexing run.
+#[derive(Debug, Clone)]
+pub(crate) enum Change {
+    /// The file was indexed (or re-indexed); its symbols may have changed.
+    Indexed(PathBuf),
+
+    /// The file was removed from the index.
+    Deindexed(PathBuf),
+}
+
+fn registry() -> &'static Mutex<HashMap<String, broadcast::Sender<Change>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, broadcast::Sender<Change>>>> = OnceLock::new();
+
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Get (creating if necessary) the broadcast sender for a given database path.
+///
+/// Every [`crate::indexer::DatabaseBackedIndexer`] and [`crate::resolver::DatabaseBackedResolver`]
+/// created against the same database path share the same sender, since they're both derived
+/// deterministically from [`crate::utils::get_database_path`].
+pub(crate) fn sender_for(database_path: &Path) -> broadcast::Sender<Change> {
+    let key = database_path.to_string_lossy().into_owned();
+
+    registry()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .entry(key)
+        .or_insert_with(|| broadcast::channel(CHANGE_FEED_CAPACITY).0)
+        .clone()
+}
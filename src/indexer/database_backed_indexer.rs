@@ -1,10 +1,14 @@
 use crate::{
-    indexer::{self, Error, Indexer, types},
-    models::parsed::{FileExtension, Language},
+    change_feed,
+    indexer::{self, Error, Indexer, JobState, Progress, Rule, RuleSet, types},
+    models,
+    models::parsed::{FileExtension, Language, SymbolRole},
     parser::{self, Parser},
+    scip,
     utils::get_database_path,
 };
 use itertools::Itertools;
+use prost::Message;
 use sqlx::sqlite::SqliteConnectOptions;
 use std::{
     iter,
@@ -12,9 +16,39 @@ use std::{
     sync::Arc,
 };
 use strum::IntoEnumIterator;
-use tokio::task::JoinSet;
+use tokio::{
+    sync::{Semaphore, mpsc},
+    task::JoinSet,
+};
 use types::Result;
 
+/// A planned database write for a single file, produced by
+/// [`DatabaseBackedIndexer::plan_file_write`].
+///
+/// Parsing a file (the CPU-heavy step) can run concurrently across a pool of worker tasks, but
+/// every plan it produces is funnelled through a single writer task (see
+/// [`DatabaseBackedIndexer::index_workspace_parallel`]), since SQLite only allows one writer at
+/// a time.
+#[derive(Debug)]
+enum FileWrite {
+    /// The file's content is unchanged (hash match), so only its `indexed_at`/`mtime`/
+    /// `size_bytes` need refreshing - no re-parse or symbol re-insertion is necessary.
+    TouchMetadata {
+        file_id: i64,
+        mtime: i64,
+        size_bytes: i64,
+    },
+
+    /// The file was (re-)parsed, so its row, symbols, and occurrences all need upserting.
+    Upsert {
+        path: PathBuf,
+        mtime: i64,
+        size_bytes: i64,
+        content_hash: String,
+        symbols: std::collections::HashSet<models::parsed::Symbol>,
+    },
+}
+
 /// Indexer acts as the layer around the language-agnostic models ([`crate::models`]),
 /// and stores resulting data in an underlying data store.
 ///
@@ -26,11 +60,18 @@ use types::Result;
 /// index using an indexer automatically using filesystem events.
 #[derive(Debug, Clone)]
 pub struct DatabaseBackedIndexer {
-    #[allow(dead_code)]
     database_path: PathBuf,
     workspaces: Vec<Arc<PathBuf>>,
     pool: sqlx::Pool<sqlx::Sqlite>,
     parser: parser::treesitter::Parser,
+    rules: RuleSet,
+    tree_cache: Arc<parser::treesitter::TreeCache>,
+
+    /// Notifies any [`crate::resolver::Resolver`] connected to the same database (see
+    /// [`crate::change_feed`]) whenever a file's symbols change, so a
+    /// [`crate::resolver::StreamMode::Subscribe`] query can re-score it live instead of
+    /// requiring the caller to re-issue the query after every re-index.
+    change_feed: tokio::sync::broadcast::Sender<change_feed::Change>,
 }
 
 impl DatabaseBackedIndexer {
@@ -40,6 +81,10 @@ impl DatabaseBackedIndexer {
     /// the same storage path and deterministic iterator of workspaces, as this ensures
     /// the resolver and indexer are connecting to the same underlying database.
     ///
+    /// `rules` allows callers to control which files and directories are indexed, independent
+    /// of `.gitignore`/`.git/info/exclude`. Rules are applied in addition to, not instead of,
+    /// git's own ignore rules. Pass an empty set of rules to only rely on git.
+    ///
     /// # Errors
     ///
     /// Returns an error if the underlying database cannot be initialized successfully,
@@ -47,10 +92,13 @@ impl DatabaseBackedIndexer {
     pub async fn new<'a, 'b>(
         storage_path: &'b Path,
         workspaces: impl IntoIterator<Item = &'a Path> + Clone,
+        rules: impl IntoIterator<Item = Rule>,
     ) -> Result<Self> {
         let (database_path, pool) =
             Self::initialise_database(storage_path, workspaces.clone()).await?;
 
+        let change_feed = change_feed::sender_for(Path::new(&database_path));
+
         let indexer = Self {
             database_path: PathBuf::from(&database_path),
             pool,
@@ -60,11 +108,54 @@ impl DatabaseBackedIndexer {
                 .map(Arc::new)
                 .collect_vec(),
             parser: parser::treesitter::Parser::default(),
+            rules: RuleSet::new(&rules.into_iter().collect_vec()),
+            tree_cache: Arc::new(parser::treesitter::TreeCache::default()),
+            change_feed,
         };
 
+        indexer.resume_incomplete_jobs().await?;
+
         Ok(indexer)
     }
 
+    /// Resume any indexing jobs which were left in a non-terminal state, for example
+    /// because the process was interrupted partway through a previous run.
+    ///
+    /// Only the files a job had not yet processed are replayed; files already recorded
+    /// as completed are skipped.
+    async fn resume_incomplete_jobs(&self) -> Result<()> {
+        let running = JobStatus::Running;
+
+        let jobs = sqlx::query!(
+            r#"SELECT id, state FROM job WHERE status = ?"#,
+            running
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        for job in jobs {
+            let Ok(state) = rmp_serde::from_slice::<JobState>(&job.state) else {
+                log::error!(
+                    "Could not decode persisted state for job {}, leaving it unresumed",
+                    job.id
+                );
+
+                continue;
+            };
+
+            log::info!(
+                "Resuming indexing job {} ({} files remaining)",
+                job.id,
+                state.remaining().count()
+            );
+
+            self.run_job(job.id, state, &mut |_, _| {}).await?;
+        }
+
+        Ok(())
+    }
+
     /// Initialize the database for the given workspaces, in a particular path.
     ///
     /// This will create the database (if it does not already exist), as well as
@@ -106,10 +197,43 @@ impl DatabaseBackedIndexer {
 
     /// Index a particular file in a workspace.
     ///
+    /// Before parsing, the file's size and modification time are compared against the
+    /// stored copy. If both match, the file is presumed unchanged and indexing is
+    /// skipped entirely. If they differ, the content hash is compared instead - this
+    /// catches the case where a file was touched (or copied) without its content
+    /// actually changing, and still avoids the cost of a full parse and symbol
+    /// re-insertion. Only a genuine content hash mismatch triggers a re-parse, and even then
+    /// a cached Treesitter tree from the last parse (if any) is fed back in so only the
+    /// changed region of the file needs to be re-walked.
+    ///
+    /// This is just [`DatabaseBackedIndexer::plan_file_write`] followed immediately by
+    /// [`DatabaseBackedIndexer::apply_file_write`] - see
+    /// [`DatabaseBackedIndexer::index_workspace_parallel`] for a version that runs the two
+    /// steps on separate worker pools.
+    ///
     /// # Errors
     ///
     /// Returns an error if the file could not be indexed successfully.
     async fn index_file(&self, path: &Path) -> Result<()> {
+        let Some(write) = self.plan_file_write(path).await? else {
+            return Ok(());
+        };
+
+        self.apply_file_write(write).await
+    }
+
+    /// Parse a file, and work out what (if anything) needs writing to the database to bring
+    /// its indexed copy up to date, without touching the database itself.
+    ///
+    /// Returns `Ok(None)` if the file's size and modification time already match the indexed
+    /// copy, so nothing needs to change at all. This is the CPU/IO-heavy half of indexing a
+    /// file - safe to run concurrently across many files at once, since it never writes to the
+    /// database (see [`DatabaseBackedIndexer::index_workspace_parallel`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or parsed successfully.
+    async fn plan_file_write(&self, path: &Path) -> Result<Option<FileWrite>> {
         if !path.exists() {
             return Err(Error::InvalidPath(
                 path.to_path_buf(),
@@ -131,56 +255,230 @@ impl DatabaseBackedIndexer {
             ));
         }
 
-        let parser::treesitter::Output { index, .. } = self
+        let metadata = std::fs::metadata(path)
+            .map_err(|e| Error::InvalidPath(path.to_path_buf(), e.to_string()))?;
+
+        let size_bytes = i64::try_from(metadata.len()).unwrap_or(i64::MAX);
+        let mtime = Self::mtime_as_epoch_seconds(&metadata);
+
+        let path_str = path.to_string_lossy();
+
+        let existing = sqlx::query!(
+            r#"
+                SELECT id, size_bytes, mtime, content_hash
+                FROM file
+                WHERE path = ?
+                "#,
+            path_str
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        if let Some(existing) = &existing
+            && existing.size_bytes == Some(size_bytes)
+            && existing.mtime == Some(mtime)
+        {
+            log::debug!(
+                "File {} is unchanged (size and mtime match the indexed copy), skipping re-index",
+                path.display()
+            );
+
+            return Ok(None);
+        }
+
+        let content = tokio::fs::read(path)
+            .await
+            .map_err(parser::Error::InvalidFile)
+            .map_err(Error::ParsingFailed)?;
+        let content_hash = blake3::hash(&content).to_hex().to_string();
+
+        if let Some(existing) = &existing
+            && existing.content_hash.as_deref() == Some(content_hash.as_str())
+        {
+            log::debug!(
+                "File {} content is unchanged (hash match), skipping parse and symbol re-insertion",
+                path.display()
+            );
+
+            return Ok(Some(FileWrite::TouchMetadata {
+                file_id: existing.id,
+                mtime,
+                size_bytes,
+            }));
+        }
+
+        let ctx = self.tree_cache.prepare_context(path, &content);
+
+        let parser::treesitter::Output { index, tree } = self
             .parser
-            .parse(path, &parser::treesitter::Context::default())
+            .parse(path, &ctx)
             .await
             .map_err(Error::ParsingFailed)?;
 
+        self.tree_cache.store(path, content, tree);
+
         log::info!("Parsed file: {}", path.display());
-        let now = chrono::Utc::now();
 
+        Ok(Some(FileWrite::Upsert {
+            path: path.to_path_buf(),
+            mtime,
+            size_bytes,
+            content_hash,
+            symbols: index.symbols,
+        }))
+    }
+
+    /// Apply a plan produced by [`DatabaseBackedIndexer::plan_file_write`] to the database.
+    ///
+    /// This is the part of indexing a file that must be serialized - see
+    /// [`DatabaseBackedIndexer::index_workspace_parallel`], which funnels every plan through a
+    /// single writer task rather than calling this concurrently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried.
+    async fn apply_file_write(&self, write: FileWrite) -> Result<()> {
         let mut transaction = self
             .pool
             .begin()
             .await
             .map_err(indexer::Error::QueryFailed)?;
 
-        let file_id: i64 = {
-            let path = path.to_string_lossy();
+        let indexed_path = Self::apply_file_write_in_transaction(&mut transaction, write).await?;
 
-            sqlx::query_scalar!(
-                r#"
-                    INSERT INTO file (
-                        path,
-                        indexed_at
-                    )
-                    VALUES (?, ?)
-                    ON CONFLICT(path) DO UPDATE SET indexed_at = excluded.indexed_at
-                    RETURNING id
-                    "#,
-                path,
-                now
-            )
-            .fetch_one(&mut *transaction)
+        transaction
+            .commit()
             .await
-            .map_err(Error::QueryFailed)?
+            .map_err(indexer::Error::QueryFailed)?;
+
+        if let Some(path) = indexed_path {
+            // Only worth the broadcast send if somebody's actually subscribed (i.e. a
+            // `StreamMode::Subscribe` query is live) - `send` is cheap to call either way, it
+            // just reports how many receivers got the message.
+            let _ = self.change_feed.send(change_feed::Change::Indexed(path));
+        }
+
+        Ok(())
+    }
+
+    /// Apply a plan produced by [`DatabaseBackedIndexer::plan_file_write`] within an
+    /// already-open `transaction`, without committing it.
+    ///
+    /// Leaving the commit to the caller lets a checkpoint (e.g.
+    /// [`DatabaseBackedIndexer::checkpoint_job_in_transaction`]) be folded into the very same
+    /// transaction as the write it's checkpointing, so a crash can never leave a job's cursor
+    /// ahead of what was actually committed to the database.
+    ///
+    /// Returns the written path, for the caller to publish on the change feed once the
+    /// transaction has actually committed - `None` for a [`FileWrite::TouchMetadata`], since
+    /// no symbols changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried. The transaction is left exactly
+    /// as far along as it got - callers that want to discard a partial write on error should
+    /// drop `transaction` rather than commit it.
+    async fn apply_file_write_in_transaction(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        write: FileWrite,
+    ) -> Result<Option<PathBuf>> {
+        let (path, mtime, size_bytes, content_hash, symbols) = match write {
+            FileWrite::TouchMetadata {
+                file_id,
+                mtime,
+                size_bytes,
+            } => {
+                let now = chrono::Utc::now();
+
+                sqlx::query!(
+                    r#"
+                        UPDATE file
+                        SET indexed_at = ?, mtime = ?, size_bytes = ?
+                        WHERE id = ?
+                        "#,
+                    now,
+                    mtime,
+                    size_bytes,
+                    file_id
+                )
+                .execute(&mut **transaction)
+                .await
+                .map_err(Error::QueryFailed)?;
+
+                return Ok(None);
+            }
+            FileWrite::Upsert {
+                path,
+                mtime,
+                size_bytes,
+                content_hash,
+                symbols,
+            } => (path, mtime, size_bytes, content_hash, symbols),
         };
 
-        // Remove all the old symbols, before persisting all the current symbols
+        let path_str = path.to_string_lossy();
+        let now = chrono::Utc::now();
+
+        let file_id: i64 = sqlx::query_scalar!(
+            r#"
+                INSERT INTO file (
+                    path,
+                    indexed_at,
+                    size_bytes,
+                    mtime,
+                    content_hash
+                )
+                VALUES (?, ?, ?, ?, ?)
+                ON CONFLICT(path) DO UPDATE SET
+                    indexed_at = excluded.indexed_at,
+                    size_bytes = excluded.size_bytes,
+                    mtime = excluded.mtime,
+                    content_hash = excluded.content_hash
+                RETURNING id
+                "#,
+            path_str,
+            now,
+            size_bytes,
+            mtime,
+            content_hash
+        )
+        .fetch_one(&mut **transaction)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        // Remove all the old symbols and occurrences, before persisting the current ones
         sqlx::query!(
             r#"
                 DELETE FROM symbol WHERE file_id = ?
                 "#,
             file_id
         )
-        .execute(&mut *transaction)
+        .execute(&mut **transaction)
+        .await
+        .map_err(indexer::Error::QueryFailed)?;
+
+        sqlx::query!(
+            r#"
+                DELETE FROM occurrence WHERE file_id = ?
+                "#,
+            file_id
+        )
+        .execute(&mut **transaction)
         .await
         .map_err(indexer::Error::QueryFailed)?;
 
-        for symbol in index.symbols {
-            let Some(definition) = symbol.definition else {
-                log::warn!("Symbol {} has no definition, skipping", symbol.name);
+        for symbol in symbols {
+            let Some(definition) = &symbol.definition else {
+                log::warn!(
+                    "Symbol {} has no definition, only its occurrences will be persisted",
+                    symbol.name
+                );
+
+                for occurrence in &symbol.occurrences {
+                    Self::persist_occurrence(transaction, &symbol.name, occurrence, file_id, now)
+                        .await?;
+                }
 
                 continue;
             };
@@ -212,12 +510,15 @@ impl DatabaseBackedIndexer {
             let end_column: i32 = i32::try_from(range.end_column)
                 .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
 
+            let container = symbol.container.as_ref().map(|container| container.join("::"));
+
             // Create new symbols
             sqlx::query!(
                 r#"
                     INSERT INTO symbol (
                         kind,
                         name,
+                        container,
                         file_id,
                         start_line,
                         start_column,
@@ -225,10 +526,11 @@ impl DatabaseBackedIndexer {
                         end_column,
                         indexed_at
                     )
-                    VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
                     "#,
                 symbol.kind,
                 symbol.name,
+                container,
                 file_id,
                 start_line,
                 start_column,
@@ -236,187 +538,1138 @@ impl DatabaseBackedIndexer {
                 end_column,
                 now
             )
-            .execute(&mut *transaction)
+            .execute(&mut **transaction)
             .await
             .map_err(Error::QueryFailed)?;
+
+            Self::persist_occurrence(transaction, &symbol.name, definition, file_id, now)
+                .await?;
+
+            for occurrence in &symbol.occurrences {
+                Self::persist_occurrence(transaction, &symbol.name, occurrence, file_id, now)
+                    .await?;
+            }
         }
 
         // TODO: File bloom filter here?
-        transaction
-            .commit()
-            .await
-            .map_err(indexer::Error::QueryFailed)?;
 
-        Ok(())
+        Ok(Some(path))
     }
-}
 
-impl Indexer for DatabaseBackedIndexer {
-    /// Get the list of workspaces currently being managed by the indexer.
-    fn get_workspaces(&self) -> Vec<Arc<PathBuf>> {
-        self.workspaces.clone()
+    /// Convert a file's modification time into seconds since the Unix epoch.
+    ///
+    /// Falls back to `0` if the platform doesn't support modification times, or the
+    /// modification time is somehow before the epoch.
+    fn mtime_as_epoch_seconds(metadata: &std::fs::Metadata) -> i64 {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .and_then(|duration| i64::try_from(duration.as_secs()).ok())
+            .unwrap_or_default()
     }
 
-    fn is_inside_workspace(&self, path: &Path) -> bool {
-        self.workspaces
-            .iter()
-            .any(|workspace| path.starts_with(workspace.as_ref()))
+    /// Persist a single occurrence of a symbol, tagged with its role (definition or
+    /// reference), so that the resolver can later answer find-references queries.
+    async fn persist_occurrence(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        symbol_name: &str,
+        occurrence: &models::parsed::Occurrence,
+        file_id: i64,
+        indexed_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        let range = &occurrence.range;
+
+        let start_line: i32 = i32::try_from(range.start_line)
+            .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+        let start_column: i32 = i32::try_from(range.start_column)
+            .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+        let end_line: i32 = i32::try_from(range.end_line)
+            .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+        let end_column: i32 = i32::try_from(range.end_column)
+            .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+
+        let role = if occurrence.roles.contains(&SymbolRole::Definition) {
+            "Definition"
+        } else {
+            "Reference"
+        };
+
+        sqlx::query!(
+            r#"
+                INSERT INTO occurrence (
+                    symbol_name,
+                    file_id,
+                    role,
+                    start_line,
+                    start_column,
+                    end_line,
+                    end_column,
+                    indexed_at
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            symbol_name,
+            file_id,
+            role,
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+            indexed_at
+        )
+        .execute(&mut **transaction)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        Ok(())
     }
 
-    /// Run indexing on all relevant files in all workspaces.
+    /// Walk a path, yielding every file which should be indexed.
     ///
-    /// # Errors
+    /// If `path` is a single file, the walk is short-circuited to just that file. Otherwise,
+    /// the path is walked recursively, applying both the supported file extensions and any
+    /// configured [`Rule`]s.
     ///
-    /// Returns a list of errors for each workspace which could not be successfully indexed.
-    async fn index_workspaces(&self) -> std::result::Result<(), Vec<indexer::Error>> {
-        let mut errors = vec![];
-        for workspace in &*self.workspaces {
-            // TODO: For indexes that already exist this will prove to be inefficient. We should
-            // hash the file content and only the parts of the workspace which have not changed.
-            // Currently, this will fully re-index the workspace even if no files have changed.
-            if let Err(e) = self.index(workspace.as_path()).await {
-                errors.push(e);
-            }
+    /// `max_depth` is forwarded directly to [`ignore::WalkBuilder::max_depth`]; pass `None`
+    /// for a full recursive walk, or `Some(1)` to only visit `path`'s immediate children.
+    fn walk_files(
+        &self,
+        path: &Path,
+        max_depth: Option<usize>,
+    ) -> Box<dyn Iterator<Item = std::result::Result<PathBuf, ignore::Error>> + Send> {
+        if !path.is_dir() {
+            // If it's a file, we can short-circuit and just index that single file
+            return Box::new(iter::once(Ok(path.to_path_buf())));
         }
 
-        if !errors.is_empty() {
-            return Err(errors);
+        // If it's a directory, we need to walk the directory and find all relevant files to
+        // index, based on the supported file extensions
+        let mut types = ignore::types::TypesBuilder::new();
+        for language in Language::iter() {
+            let file_extension = &*FileExtension::from(language);
+
+            if let Err(e) = types.add(file_extension, &format!("*.{file_extension}")) {
+                log::error!(
+                    "File extension ({file_extension}) could not be added to indexer: {e}"
+                );
+
+                continue;
+            }
+
+            types.select(file_extension);
         }
+        let types = types.build().expect("Failed to build ignore types");
+
+        let rules = self.rules.clone();
+
+        let walker = ignore::WalkBuilder::new(path)
+            .types(types)
+            .max_depth(max_depth)
+            .git_ignore(true)
+            .git_exclude(true)
+            .filter_entry(move |entry| {
+                // Directories rejected by a rule prune their whole subtree, rather than
+                // being filtered file-by-file.
+                !entry.file_type().is_some_and(|t| t.is_dir()) || !rules.is_dir_rejected(entry.path())
+            })
+            .build();
+
+        let rules = self.rules.clone();
+
+        Box::new(walker.into_iter().filter_map(move |entry| match entry {
+            Ok(entry) => {
+                if !entry.metadata().map(|m| m.is_file()).unwrap_or(false) {
+                    return None;
+                }
 
-        Ok(())
+                if !rules.is_file_accepted(entry.path()) {
+                    return None;
+                }
+
+                Some(Ok(entry.into_path()))
+            }
+            Err(e) => Some(Err(e)),
+        }))
     }
 
-    /// Index a particular file, or folder, inside a workspace.
+    /// Index all relevant files inside a workspace directory, tracking progress in a
+    /// resumable job in the `job` table.
+    ///
+    /// Unlike [`Indexer::index`], files are indexed sequentially rather than concurrently,
+    /// since each completed file's progress is checkpointed as it finishes - this is what
+    /// allows [`DatabaseBackedIndexer::new`] to resume an interrupted job by replaying only
+    /// the files which were not yet processed.
     ///
     /// # Errors
     ///
-    /// Returns an error if the folder could not be successfully indexed.
-    async fn index(&self, path: &Path) -> Result<()> {
-        if !path.exists() {
+    /// Returns an error if the job could not be created or checkpointed.
+    pub async fn index_workspace_with_job(
+        &self,
+        workspace: &Path,
+        mut on_progress: impl FnMut(Progress, &Path) + Send,
+    ) -> Result<()> {
+        if !self.is_inside_workspace(workspace) {
             return Err(Error::InvalidPath(
-                path.to_path_buf(),
-                "Path does not exist".into(),
+                workspace.to_path_buf(),
+                "Path is not inside any registered workspace".into(),
             ));
         }
 
-        if !self.is_inside_workspace(path) {
+        let mut files = self
+            .walk_files(workspace, None)
+            .filter_map(std::result::Result::ok)
+            .collect_vec();
+
+        // The walk order `ignore::WalkBuilder` produces is unspecified, but a [`JobCursor`]
+        // only makes sense as a position in a stable ordering, so the files are sorted before
+        // the job's state is ever persisted.
+        files.sort();
+
+        let state = JobState::new(files);
+        let job_id = self.create_job(workspace, &state).await?;
+
+        self.run_job(job_id, state, &mut on_progress).await
+    }
+
+    /// Resume a `Running` job for `workspace`, or start a fresh one (see
+    /// [`DatabaseBackedIndexer::index_workspace_with_job`]) if it has none.
+    ///
+    /// Unlike [`DatabaseBackedIndexer::index_workspace_with_job`], which always starts a brand
+    /// new job and walk regardless of what's already in flight, this looks for the most
+    /// recent job left `Running` against `workspace` first, and continues it from its
+    /// persisted [`indexer::JobCursor`] instead of re-walking and re-indexing files it already
+    /// completed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job table could not be queried, or the job could not be resumed
+    /// or created.
+    pub async fn resume_workspace_with_job(
+        &self,
+        workspace: &Path,
+        on_progress: impl FnMut(Progress, &Path) + Send,
+    ) -> Result<()> {
+        if !self.is_inside_workspace(workspace) {
             return Err(Error::InvalidPath(
-                path.to_path_buf(),
+                workspace.to_path_buf(),
                 "Path is not inside any registered workspace".into(),
             ));
         }
 
-        let files: Box<dyn Iterator<Item = std::result::Result<PathBuf, _>> + Send> =
-            if path.is_dir() {
-                // If it's a directory, we need to walk the directory and find all relevant files to
-                // index, based on the supported file extensions
-                let mut types = ignore::types::TypesBuilder::new();
-                for language in Language::iter() {
-                    let file_extension = &*FileExtension::from(language);
+        let running = JobStatus::Running;
+        let workspace_str = workspace.to_string_lossy();
 
-                    if let Err(e) = types.add(file_extension, &format!("*.{file_extension}")) {
-                        log::error!(
-                            "File extension ({file_extension}) could not be added to indexer: {e}"
-                        );
+        let job = sqlx::query!(
+            r#"SELECT id, state FROM job WHERE workspace = ? AND status = ? ORDER BY id DESC LIMIT 1"#,
+            workspace_str,
+            running
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        if let Some(job) = job {
+            match rmp_serde::from_slice::<JobState>(&job.state) {
+                Ok(state) if self.completed_files_unchanged(&state).await => {
+                    log::info!(
+                        "Resuming indexing job {} for {} ({} files remaining)",
+                        job.id,
+                        workspace.display(),
+                        state.remaining().count()
+                    );
+
+                    let mut on_progress = on_progress;
+                    return self.run_job(job.id, state, &mut on_progress).await;
+                }
+                Ok(_) => {
+                    log::info!(
+                        "Job {} for {} has files modified since its checkpoint, starting a fresh index instead",
+                        job.id,
+                        workspace.display()
+                    );
+                }
+                Err(_) => {
+                    log::error!(
+                        "Could not decode persisted state for job {}, starting a fresh index of {} instead",
+                        job.id,
+                        workspace.display()
+                    );
+                }
+            }
+        }
 
-                        continue;
-                    }
+        self.index_workspace_with_job(workspace, on_progress).await
+    }
 
-                    types.select(file_extension);
-                }
-                let types = types.build().expect("Failed to build ignore types");
-
-                let walker = ignore::WalkBuilder::new(path)
-                    .types(types)
-                    .git_ignore(true)
-                    .git_exclude(true)
-                    .build();
-
-                Box::new(walker.into_iter().filter_map(|entry| match entry {
-                    Ok(entry) => {
-                        if entry.metadata().map(|m| m.is_file()).unwrap_or(false) {
-                            Some(Ok(entry.into_path()))
-                        } else {
-                            None
-                        }
-                    }
-                    Err(e) => Some(Err(e)),
-                }))
-            } else {
-                // If it's a file, we can short-circuit and just index that single file
-                Box::new(iter::once(Ok(path.to_path_buf())))
+    /// Whether every file in `state`'s already-completed prefix still has the same on-disk
+    /// modification time it was indexed with, i.e. nothing has changed underneath the
+    /// persisted cursor since the job was last checkpointed.
+    ///
+    /// A job can only safely skip straight to its cursor when this holds - if a file has been
+    /// modified (or removed) since it was marked complete, resuming from the cursor would skip
+    /// over it for good, so the caller should fall back to a fresh walk instead.
+    async fn completed_files_unchanged(&self, state: &JobState) -> bool {
+        let Some(cursor) = &state.cursor else {
+            return true;
+        };
+
+        for file in state.completed() {
+            let Ok(metadata) = std::fs::metadata(file) else {
+                return false;
             };
 
-        let mut tasks = JoinSet::<()>::new();
+            let mtime = Self::mtime_as_epoch_seconds(&metadata);
 
-        for result in files {
-            match result {
-                Ok(entry) => {
-                    let indexer = self.clone();
+            // The cursor already carries the mtime of the last completed file, so only the
+            // files before it need a database round-trip to find out what they were indexed
+            // with.
+            let indexed_mtime = if file == &cursor.path {
+                Some(cursor.mtime)
+            } else {
+                let path_str = file.to_string_lossy();
+
+                sqlx::query!(r#"SELECT mtime FROM file WHERE path = ?"#, path_str)
+                    .fetch_optional(&self.pool)
+                    .await
+                    .ok()
+                    .flatten()
+                    .and_then(|row| row.mtime)
+            };
 
-                    tasks.spawn(async move {
-                        if let Err(e) = indexer.index_file(entry.as_path()).await {
-                            log::error!("Error indexing file {}: {e:?}", entry.display());
-                        }
-                    });
-                }
-                Err(e) => {
-                    log::error!("Error while walking project directory: {e:?}");
-                }
+            if indexed_mtime != Some(mtime) {
+                return false;
             }
         }
 
-        tasks.join_all().await;
-
-        Ok(())
+        true
     }
 
-    /// De-index a particular file, or folder, in a workspace.
+    /// Index all relevant files inside a workspace directory, using a bounded pool of worker
+    /// tasks to parse files concurrently rather than one at a time.
     ///
-    /// Usually, this is necessary when a previously indexed file is deleted.
+    /// Parsing and symbol extraction for each file (see [`DatabaseBackedIndexer::plan_file_write`])
+    /// runs concurrently across up to `max_concurrency` worker tasks - pass `None` to default to
+    /// [`std::thread::available_parallelism`]. The resulting database writes (see
+    /// [`DatabaseBackedIndexer::apply_file_write`]) are funnelled through a single writer task
+    /// over an `mpsc` channel instead, since SQLite only allows one writer at a time. Unlike
+    /// [`DatabaseBackedIndexer::index_workspace_with_job`], progress is not checkpointed, so an
+    /// interrupted run has to be repeated from scratch - this is intended for a cold, first-time
+    /// index of a large workspace, where cutting wall-clock time matters more than resumability.
+    ///
+    /// A file which fails to index does not abort the rest of the walk - its error is collected
+    /// and returned alongside every other failure once the workspace has been fully walked.
     ///
     /// # Errors
     ///
-    /// Returns an error if the file could not be de-indexed successfully.
-    async fn deindex(&self, path: &Path) -> Result<()> {
-        let path_pattern = format!("{}%", path.display());
+    /// Returns a list of errors, one for each file which could not be indexed successfully.
+    pub async fn index_workspace_parallel(
+        &self,
+        workspace: &Path,
+        max_concurrency: Option<usize>,
+    ) -> std::result::Result<(), Vec<Error>> {
+        if !self.is_inside_workspace(workspace) {
+            return Err(vec![Error::InvalidPath(
+                workspace.to_path_buf(),
+                "Path is not inside any registered workspace".into(),
+            )]);
+        }
 
-        // Removing the file will trigger a removal of any associated symbols as the FK
-        // is set to cascade delete
-        sqlx::query!(r#"DELETE FROM file WHERE path LIKE ?"#, path_pattern)
-            .execute(&self.pool)
-            .await
-            .map_err(indexer::Error::QueryFailed)?;
+        let max_concurrency = max_concurrency
+            .or_else(|| std::thread::available_parallelism().ok().map(Into::into))
+            .unwrap_or(1);
 
-        Ok(())
-    }
-}
+        let files = self
+            .walk_files(workspace, None)
+            .filter_map(std::result::Result::ok)
+            .collect_vec();
 
-#[cfg(test)]
-mod tests {
-    use std::path::PathBuf;
+        let (tx, mut rx) = mpsc::channel::<FileWrite>(max_concurrency);
+        let semaphore = Arc::new(Semaphore::new(max_concurrency));
+        let errors = Arc::new(std::sync::Mutex::new(Vec::new()));
 
-    use insta::assert_json_snapshot;
-    use tempfile::tempdir;
-    use tokio_stream::StreamExt;
+        let writer = {
+            let indexer = self.clone();
+            let errors = Arc::clone(&errors);
 
-    use crate::{
-        indexer::Indexer,
-        models,
-        resolver::{self, Resolver},
-    };
+            tokio::spawn(async move {
+                while let Some(write) = rx.recv().await {
+                    if let Err(e) = indexer.apply_file_write(write).await {
+                        errors.lock().expect("writer error lock should never be poisoned").push(e);
+                    }
+                }
+            })
+        };
 
-    #[tokio::test]
-    pub async fn test_indexing_project() {
-        let storage_path = tempdir()
-            .expect("Should never fail when creating a temporary path for testing indexing");
+        let mut workers = JoinSet::<()>::new();
 
-        let fixtures = PathBuf::from("tests/fixtures/");
+        for file in files {
+            let permit = Arc::clone(&semaphore)
+                .acquire_owned()
+                .await
+                .expect("semaphore is never closed while workers are running");
+            let indexer = self.clone();
+            let tx = tx.clone();
+            let errors = Arc::clone(&errors);
+
+            workers.spawn(async move {
+                let _permit = permit;
+
+                match indexer.plan_file_write(&file).await {
+                    Ok(Some(write)) => {
+                        // The writer task only ever stops once every sender (including this
+                        // one) has been dropped, so a closed channel here means it already
+                        // exited abnormally - nothing left to do but drop the plan.
+                        let _ = tx.send(write).await;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        errors
+                            .lock()
+                            .expect("worker error lock should never be poisoned")
+                            .push(e);
+                    }
+                }
+            });
+        }
 
-        let workspaces = vec![fixtures.as_path()];
+        workers.join_all().await;
 
-        let indexer = super::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone())
-            .await
+        // Every worker (and its clone of `tx`) has exited, so dropping our own copy closes the
+        // channel, which lets the writer task drain whatever's left and then return.
+        drop(tx);
+        let _ = writer.await;
+
+        let errors = Arc::try_unwrap(errors)
+            .expect("no other references to the error list should remain")
+            .into_inner()
+            .expect("error lock should never be poisoned");
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(())
+    }
+
+    /// Create a new, `Running`, job in the `job` table for a given workspace and initial
+    /// state.
+    async fn create_job(&self, workspace: &Path, state: &JobState) -> Result<i64> {
+        let workspace_str = workspace.to_string_lossy();
+        let now = chrono::Utc::now();
+        let status = JobStatus::Running;
+        let encoded =
+            rmp_serde::to_vec(state).expect("JobState should always serialize successfully");
+
+        let job_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO job (workspace, status, state, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?)
+                RETURNING id
+                "#,
+            workspace_str,
+            status,
+            encoded,
+            now,
+            now
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        Ok(job_id)
+    }
+
+    /// Process the remaining, not-yet-completed, files of a job, checkpointing progress
+    /// after each file and marking the job `Completed` once every file has been processed.
+    async fn run_job(
+        &self,
+        job_id: i64,
+        mut state: JobState,
+        on_progress: &mut (impl FnMut(Progress, &Path) + Send),
+    ) -> Result<()> {
+        let remaining = state.remaining().cloned().collect_vec();
+
+        for file in remaining {
+            self.index_file_with_checkpoint(file.as_path(), job_id, &mut state)
+                .await?;
+
+            on_progress(state.progress(), file.as_path());
+        }
+
+        self.complete_job(job_id).await
+    }
+
+    /// Index a single file belonging to a job, and checkpoint the job's [`JobCursor`] past it
+    /// in the very same transaction as the symbol writes, so a crash between the two can never
+    /// leave the persisted cursor ahead of what was actually committed.
+    ///
+    /// A file which fails to plan or apply is logged and the cursor still advances past it
+    /// (mirroring [`DatabaseBackedIndexer::index_workspace_parallel`]'s "don't let one bad file
+    /// abort the rest of the walk" behavior) - but a *partial* write (i.e. one that failed
+    /// partway through `apply_file_write_in_transaction`) is rolled back rather than committed
+    /// alongside the checkpoint, so the file is only ever marked done if its symbols either
+    /// fully landed or were never touched at all.
+    async fn index_file_with_checkpoint(
+        &self,
+        file: &Path,
+        job_id: i64,
+        state: &mut JobState,
+    ) -> Result<()> {
+        let plan = match self.plan_file_write(file).await {
+            Ok(plan) => plan,
+            Err(e) => {
+                log::error!("Error indexing file {}: {e:?}", file.display());
+                None
+            }
+        };
+
+        let mtime = std::fs::metadata(file)
+            .ok()
+            .map(|metadata| Self::mtime_as_epoch_seconds(&metadata))
+            .unwrap_or_default();
+
+        state.advance(file, mtime);
+
+        let Some(plan) = plan else {
+            return self.checkpoint_job(job_id, state).await;
+        };
+
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(indexer::Error::QueryFailed)?;
+
+        let indexed_path = match Self::apply_file_write_in_transaction(&mut transaction, plan).await
+        {
+            Ok(indexed_path) => indexed_path,
+            Err(e) => {
+                log::error!("Error indexing file {}: {e:?}", file.display());
+
+                // Dropping the transaction here, rather than committing it, rolls back
+                // whatever partial write it managed before failing.
+                drop(transaction);
+
+                return self.checkpoint_job(job_id, state).await;
+            }
+        };
+
+        Self::checkpoint_job_in_transaction(&mut transaction, job_id, state).await?;
+
+        transaction
+            .commit()
+            .await
+            .map_err(indexer::Error::QueryFailed)?;
+
+        if let Some(path) = indexed_path {
+            let _ = self.change_feed.send(change_feed::Change::Indexed(path));
+        }
+
+        Ok(())
+    }
+
+    /// Persist the current state of a job, so it can be resumed from this point if the
+    /// process is interrupted before the job completes.
+    async fn checkpoint_job(&self, job_id: i64, state: &JobState) -> Result<()> {
+        let now = chrono::Utc::now();
+        let encoded =
+            rmp_serde::to_vec(state).expect("JobState should always serialize successfully");
+
+        sqlx::query!(
+            r#"UPDATE job SET state = ?, updated_at = ? WHERE id = ?"#,
+            encoded,
+            now,
+            job_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        Ok(())
+    }
+
+    /// Persist the current state of a job within an already-open `transaction`, without
+    /// committing it - see [`DatabaseBackedIndexer::checkpoint_job`].
+    async fn checkpoint_job_in_transaction(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        job_id: i64,
+        state: &JobState,
+    ) -> Result<()> {
+        let now = chrono::Utc::now();
+        let encoded =
+            rmp_serde::to_vec(state).expect("JobState should always serialize successfully");
+
+        sqlx::query!(
+            r#"UPDATE job SET state = ?, updated_at = ? WHERE id = ?"#,
+            encoded,
+            now,
+            job_id
+        )
+        .execute(&mut **transaction)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        Ok(())
+    }
+
+    /// Mark a job as `Completed`, once every file it discovered has been processed.
+    async fn complete_job(&self, job_id: i64) -> Result<()> {
+        let now = chrono::Utc::now();
+        let status = JobStatus::Completed;
+
+        sqlx::query!(
+            r#"UPDATE job SET status = ?, updated_at = ? WHERE id = ?"#,
+            status,
+            now,
+            job_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        Ok(())
+    }
+
+    /// Export every symbol and occurrence indexed for a workspace as a SCIP [`scip::Index`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying database could not be queried.
+    pub async fn export_scip(&self, workspace: &Path) -> Result<scip::Index> {
+        let workspace_pattern = format!("{}%", workspace.display());
+
+        let files = sqlx::query!(
+            r#"SELECT id, path FROM file WHERE path LIKE ?"#,
+            workspace_pattern
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        let mut documents = Vec::with_capacity(files.len());
+
+        for file in files {
+            let occurrences = sqlx::query!(
+                r#"
+                    SELECT symbol_name, role, start_line, start_column, end_line, end_column
+                    FROM occurrence
+                    WHERE file_id = ?
+                    "#,
+                file.id
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Error::QueryFailed)?;
+
+            let symbol_rows = sqlx::query!(
+                r#"SELECT name, kind FROM symbol WHERE file_id = ?"#,
+                file.id
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(Error::QueryFailed)?;
+
+            let kinds_by_name: std::collections::HashMap<String, models::parsed::SymbolKind> =
+                symbol_rows
+                    .into_iter()
+                    .map(|row| {
+                        (
+                            row.name,
+                            row.kind.parse().unwrap_or(models::parsed::SymbolKind::Unknown),
+                        )
+                    })
+                    .collect();
+
+            let relative_path = Path::new(&file.path)
+                .strip_prefix(workspace)
+                .map_or_else(|_| file.path.clone(), |path| path.to_string_lossy().into_owned());
+
+            let mut symbols = std::collections::HashSet::new();
+            let mut scip_occurrences = Vec::with_capacity(occurrences.len());
+
+            for occurrence in occurrences {
+                symbols.insert(occurrence.symbol_name.clone());
+
+                let roles = if occurrence.role == "Definition" {
+                    models::parsed::Roles(vec![models::parsed::SymbolRole::Definition])
+                } else {
+                    models::parsed::Roles(vec![models::parsed::SymbolRole::Reference])
+                };
+
+                let range = models::parsed::Range::new(
+                    usize::try_from(occurrence.start_line).unwrap_or_default(),
+                    usize::try_from(occurrence.end_line).unwrap_or_default(),
+                    usize::try_from(occurrence.start_column).unwrap_or_default(),
+                    usize::try_from(occurrence.end_column).unwrap_or_default(),
+                );
+
+                scip_occurrences.push(scip::Occurrence {
+                    range: scip::range_to_scip(&range),
+                    symbol: occurrence.symbol_name,
+                    symbol_roles: scip::roles_to_scip(&roles),
+                });
+            }
+
+            documents.push(scip::Document {
+                relative_path,
+                language: String::new(),
+                occurrences: scip_occurrences,
+                symbols: symbols
+                    .into_iter()
+                    .map(|name| {
+                        let kind = kinds_by_name
+                            .get(&name)
+                            .copied()
+                            .unwrap_or(models::parsed::SymbolKind::Unknown);
+
+                        scip::SymbolInformation {
+                            symbol: name.clone(),
+                            display_name: name,
+                            kind: scip::kind_to_scip(kind) as i32,
+                        }
+                    })
+                    .collect(),
+            });
+        }
+
+        Ok(scip::Index {
+            metadata: Some(scip::Metadata {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                tool_info: Some(scip::ToolInfo {
+                    name: env!("CARGO_PKG_NAME").to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                }),
+                project_root: workspace.to_string_lossy().into_owned(),
+            }),
+            documents,
+        })
+    }
+
+    /// Export a workspace's index as a SCIP index, and write it to `output_path` in its
+    /// standard protobuf wire format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried, or the file could not be
+    /// written.
+    pub async fn export_scip_to_file(&self, workspace: &Path, output_path: &Path) -> Result<()> {
+        let index = self.export_scip(workspace).await?;
+
+        tokio::fs::write(output_path, index.encode_to_vec())
+            .await
+            .map_err(|e| Error::ScipFileError(output_path.to_path_buf(), e))
+    }
+
+    /// Read a SCIP index from its standard protobuf wire format at `input_path`, and import
+    /// it for `workspace`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read, the protobuf could not be decoded, or
+    /// a row could not be inserted.
+    pub async fn import_scip_from_file(&self, workspace: &Path, input_path: &Path) -> Result<()> {
+        let bytes = tokio::fs::read(input_path)
+            .await
+            .map_err(|e| Error::ScipFileError(input_path.to_path_buf(), e))?;
+
+        let index = scip::Index::decode(bytes.as_slice()).map_err(Error::ScipDecodingFailed)?;
+
+        self.import_scip(workspace, index).await
+    }
+
+    /// Import a SCIP [`scip::Index`] (produced by onoma or another SCIP-compatible tool) into
+    /// the database, so its symbols can be resolved alongside those onoma parsed itself.
+    ///
+    /// Only definition occurrences are written into the `symbol` table, since that's the table
+    /// [`crate::resolver::Resolver`] queries; every occurrence (definitions and references
+    /// alike) is also written into the `occurrence` table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a row could not be inserted.
+    pub async fn import_scip(&self, workspace: &Path, index: scip::Index) -> Result<()> {
+        let now = chrono::Utc::now();
+
+        let mut transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(indexer::Error::QueryFailed)?;
+
+        for document in index.documents {
+            let absolute_path = workspace.join(&document.relative_path);
+            let path_str = absolute_path.to_string_lossy();
+
+            let file_id: i64 = sqlx::query_scalar!(
+                r#"
+                    INSERT INTO file (path, indexed_at)
+                    VALUES (?, ?)
+                    ON CONFLICT(path) DO UPDATE SET indexed_at = excluded.indexed_at
+                    RETURNING id
+                    "#,
+                path_str,
+                now
+            )
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(Error::QueryFailed)?;
+
+            sqlx::query!(r#"DELETE FROM symbol WHERE file_id = ?"#, file_id)
+                .execute(&mut *transaction)
+                .await
+                .map_err(Error::QueryFailed)?;
+
+            sqlx::query!(r#"DELETE FROM occurrence WHERE file_id = ?"#, file_id)
+                .execute(&mut *transaction)
+                .await
+                .map_err(Error::QueryFailed)?;
+
+            let kinds_by_symbol: std::collections::HashMap<&str, scip::Kind> = document
+                .symbols
+                .iter()
+                .map(|symbol| (symbol.symbol.as_str(), symbol.kind()))
+                .collect();
+
+            for occurrence in document.occurrences {
+                let Some(range) = scip::range_from_scip(&occurrence.range) else {
+                    log::warn!(
+                        "SCIP occurrence for {} had a malformed range, skipping",
+                        occurrence.symbol
+                    );
+
+                    continue;
+                };
+
+                let is_definition = scip::roles_from_scip(occurrence.symbol_roles)
+                    .contains(&models::parsed::SymbolRole::Definition);
+
+                let start_line: i32 = i32::try_from(range.start_line)
+                    .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+                let start_column: i32 = i32::try_from(range.start_column)
+                    .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+                let end_line: i32 = i32::try_from(range.end_line)
+                    .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+                let end_column: i32 = i32::try_from(range.end_column)
+                    .map_err(|_| indexer::Error::InvalidRange(range.clone()))?;
+
+                if is_definition {
+                    let kind = kinds_by_symbol
+                        .get(occurrence.symbol.as_str())
+                        .copied()
+                        .map_or(models::parsed::SymbolKind::Unknown, scip::kind_from_scip);
+
+                    sqlx::query!(
+                        r#"
+                            INSERT INTO symbol (
+                                kind, name, file_id, start_line, start_column, end_line, end_column, indexed_at
+                            )
+                            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                            "#,
+                        kind,
+                        occurrence.symbol.as_str(),
+                        file_id,
+                        start_line,
+                        start_column,
+                        end_line,
+                        end_column,
+                        now
+                    )
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(Error::QueryFailed)?;
+                }
+
+                let role = if is_definition { "Definition" } else { "Reference" };
+
+                sqlx::query!(
+                    r#"
+                        INSERT INTO occurrence (
+                            symbol_name, file_id, role, start_line, start_column, end_line, end_column, indexed_at
+                        )
+                        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                        "#,
+                    occurrence.symbol,
+                    file_id,
+                    role,
+                    start_line,
+                    start_column,
+                    end_line,
+                    end_column,
+                    now
+                )
+                .execute(&mut *transaction)
+                .await
+                .map_err(Error::QueryFailed)?;
+            }
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(indexer::Error::QueryFailed)?;
+
+        Ok(())
+    }
+}
+
+impl Indexer for DatabaseBackedIndexer {
+    /// Get the list of workspaces currently being managed by the indexer.
+    fn get_workspaces(&self) -> Vec<Arc<PathBuf>> {
+        self.workspaces.clone()
+    }
+
+    fn is_inside_workspace(&self, path: &Path) -> bool {
+        self.workspaces
+            .iter()
+            .any(|workspace| path.starts_with(workspace.as_ref()))
+    }
+
+    /// Run indexing on all relevant files in all workspaces.
+    ///
+    /// # Errors
+    ///
+    /// Returns a list of errors for each workspace which could not be successfully indexed.
+    async fn index_workspaces(&self) -> std::result::Result<(), Vec<indexer::Error>> {
+        let mut errors = vec![];
+        for workspace in &*self.workspaces {
+            if let Err(e) = self.index(workspace.as_path()).await {
+                errors.push(e);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(())
+    }
+
+    /// Index all workspaces, tracking resumable progress for each in the `job` table.
+    ///
+    /// See [`DatabaseBackedIndexer::index_workspace_with_job`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a list of errors for each workspace which could not be successfully indexed.
+    async fn index_workspaces_with_job(&self) -> std::result::Result<(), Vec<indexer::Error>> {
+        let mut errors = vec![];
+        for workspace in &*self.workspaces {
+            if let Err(e) = self
+                .index_workspace_with_job(workspace.as_path(), |_, _| {})
+                .await
+            {
+                errors.push(e);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(())
+    }
+
+    /// Resume a `Running` job for each workspace, or start a fresh one for any workspace that
+    /// has none.
+    ///
+    /// See [`DatabaseBackedIndexer::resume_workspace_with_job`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a list of errors for each workspace which could not be successfully indexed.
+    async fn resume_workspaces_with_job(&self) -> std::result::Result<(), Vec<indexer::Error>> {
+        let mut errors = vec![];
+        for workspace in &*self.workspaces {
+            if let Err(e) = self
+                .resume_workspace_with_job(workspace.as_path(), |_, _| {})
+                .await
+            {
+                errors.push(e);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(())
+    }
+
+    /// Index all workspaces, using a bounded pool of worker tasks per workspace to parse
+    /// files concurrently.
+    ///
+    /// See [`DatabaseBackedIndexer::index_workspace_parallel`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a list of errors, one for each file which could not be indexed successfully,
+    /// across every workspace.
+    async fn index_workspaces_parallel(
+        &self,
+        max_concurrency: Option<usize>,
+    ) -> std::result::Result<(), Vec<indexer::Error>> {
+        let mut errors = vec![];
+        for workspace in &*self.workspaces {
+            if let Err(e) = self
+                .index_workspace_parallel(workspace.as_path(), max_concurrency)
+                .await
+            {
+                errors.extend(e);
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(())
+    }
+
+    /// Index a particular file, or folder, inside a workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder could not be successfully indexed.
+    async fn index(&self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(Error::InvalidPath(
+                path.to_path_buf(),
+                "Path does not exist".into(),
+            ));
+        }
+
+        if !self.is_inside_workspace(path) {
+            return Err(Error::InvalidPath(
+                path.to_path_buf(),
+                "Path is not inside any registered workspace".into(),
+            ));
+        }
+
+        let files = self.walk_files(path, None);
+
+        let mut tasks = JoinSet::<()>::new();
+
+        for result in files {
+            match result {
+                Ok(entry) => {
+                    let indexer = self.clone();
+
+                    tasks.spawn(async move {
+                        if let Err(e) = indexer.index_file(entry.as_path()).await {
+                            log::error!("Error indexing file {}: {e:?}", entry.display());
+                        }
+                    });
+                }
+                Err(e) => {
+                    log::error!("Error while walking project directory: {e:?}");
+                }
+            }
+        }
+
+        tasks.join_all().await;
+
+        Ok(())
+    }
+
+    /// Index only the immediate children of a folder, inside a workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder could not be successfully indexed.
+    async fn index_shallow(&self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Err(Error::InvalidPath(
+                path.to_path_buf(),
+                "Path does not exist".into(),
+            ));
+        }
+
+        if !self.is_inside_workspace(path) {
+            return Err(Error::InvalidPath(
+                path.to_path_buf(),
+                "Path is not inside any registered workspace".into(),
+            ));
+        }
+
+        let files = self.walk_files(path, Some(1));
+
+        let mut tasks = JoinSet::<()>::new();
+
+        for result in files {
+            match result {
+                Ok(entry) => {
+                    let indexer = self.clone();
+
+                    tasks.spawn(async move {
+                        if let Err(e) = indexer.index_file(entry.as_path()).await {
+                            log::error!("Error indexing file {}: {e:?}", entry.display());
+                        }
+                    });
+                }
+                Err(e) => {
+                    log::error!("Error while walking project directory: {e:?}");
+                }
+            }
+        }
+
+        tasks.join_all().await;
+
+        Ok(())
+    }
+
+    /// De-index a particular file, or folder, in a workspace.
+    ///
+    /// Usually, this is necessary when a previously indexed file is deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be de-indexed successfully.
+    async fn deindex(&self, path: &Path) -> Result<()> {
+        let path_pattern = format!("{}%", path.display());
+
+        // Removing the file will trigger a removal of any associated symbols as the FK
+        // is set to cascade delete
+        sqlx::query!(r#"DELETE FROM file WHERE path LIKE ?"#, path_pattern)
+            .execute(&self.pool)
+            .await
+            .map_err(indexer::Error::QueryFailed)?;
+
+        self.tree_cache.evict(path);
+
+        let _ = self
+            .change_feed
+            .send(change_feed::Change::Deindexed(path.to_path_buf()));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use insta::assert_json_snapshot;
+    use tempfile::tempdir;
+    use tokio_stream::StreamExt;
+
+    use crate::{
+        indexer::Indexer,
+        models,
+        resolver::{self, Resolver},
+    };
+
+    #[tokio::test]
+    pub async fn test_indexing_project() {
+        let storage_path = tempdir()
+            .expect("Should never fail when creating a temporary path for testing indexing");
+
+        let fixtures = PathBuf::from("tests/fixtures/");
+
+        let workspaces = vec![fixtures.as_path()];
+
+        let indexer = super::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
+            .await
             .expect("Should be able to create the empty index");
 
         let resolver =
@@ -426,8 +1679,11 @@ mod tests {
 
         let mut resolved_symbols: Vec<models::resolved::ResolvedSymbol> = resolver
             .query(String::new(), resolver::Context::default())
-            .collect()
-            .await;
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
 
         // The order of symbols is not guaranteed, so we need the sort symbols to keep the
         // snapshot predictable
@@ -448,7 +1704,7 @@ mod tests {
 
         let workspaces = vec![fixtures.as_path()];
 
-        let indexer = super::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone())
+        let indexer = super::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
             .await
             .expect("Should be able to create the empty index");
 
@@ -467,8 +1723,11 @@ mod tests {
 
         let mut resolved_symbols: Vec<models::resolved::ResolvedSymbol> = resolver
             .query(String::new(), resolver::Context::default())
-            .collect()
-            .await;
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
 
         // The order of symbols is not guaranteed, so we need the sort symbols to keep the
         // snapshot predictable
@@ -479,4 +1738,64 @@ mod tests {
             {"[].id" => 0} // IDs are non-deterministic, so just blank them out
         );
     }
+
+    #[tokio::test]
+    pub async fn test_exporting_and_reimporting_a_workspace_as_scip() {
+        let storage_path = tempdir()
+            .expect("Should never fail when creating a temporary path for testing indexing");
+
+        let fixtures = PathBuf::from("tests/fixtures/");
+
+        let workspaces = vec![fixtures.as_path()];
+
+        let indexer = super::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
+            .await
+            .expect("Should be able to create the empty index");
+
+        assert!(indexer.index_workspaces().await.is_ok());
+
+        let index = indexer
+            .export_scip(fixtures.as_path())
+            .await
+            .expect("Should be able to export the workspace as a SCIP index");
+
+        assert!(!index.documents.is_empty());
+
+        let reimported_storage_path = tempdir()
+            .expect("Should never fail when creating a temporary path for testing indexing");
+
+        let reimported_indexer = super::DatabaseBackedIndexer::new(
+            reimported_storage_path.path(),
+            workspaces.clone(),
+            [],
+        )
+        .await
+        .expect("Should be able to create the empty index");
+
+        assert!(
+            reimported_indexer
+                .import_scip(fixtures.as_path(), index)
+                .await
+                .is_ok()
+        );
+
+        let resolver =
+            resolver::DatabaseBackedResolver::new(reimported_storage_path.path(), workspaces);
+
+        let resolved_symbols: Vec<models::resolved::ResolvedSymbol> = resolver
+            .query(String::new(), resolver::Context::default())
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        assert!(!resolved_symbols.is_empty());
+        assert!(
+            resolved_symbols
+                .iter()
+                .any(|symbol| symbol.kind != models::parsed::SymbolKind::Unknown),
+            "re-imported symbols should retain their original kind, not fall back to Unknown"
+        );
+    }
 }
@@ -63,4 +63,15 @@ pub enum Error {
     /// expected constraints, such as start > end or out-of-bounds positions.
     #[error("The provided range is invalid: {0:?}")]
     InvalidRange(models::parsed::Range),
+
+    /// A SCIP index could not be read from, or written to, disk.
+    ///
+    /// - `PathBuf` contains the SCIP file path.
+    /// - `std::io::Error` provides the underlying I/O error.
+    #[error("Unable to access SCIP index file ({0}): {1}")]
+    ScipFileError(PathBuf, std::io::Error),
+
+    /// A SCIP index could not be decoded from its protobuf representation.
+    #[error("Unable to decode SCIP index: {0}")]
+    ScipDecodingFailed(#[from] prost::DecodeError),
 }
@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The status of an indexing job, as persisted in the `job` table.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    sqlx::Type,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    PartialEq,
+    Eq,
+)]
+pub enum JobStatus {
+    /// The job is still in progress, or was interrupted (i.e. by a crash) before it could
+    /// finish.
+    ///
+    /// A job left in this state when the indexer starts up is eligible to be resumed.
+    Running,
+
+    /// The job finished indexing every file it discovered.
+    Completed,
+}
+
+/// A monotonically increasing marker of how far a [`JobState`] has progressed: the last file
+/// (in [`JobState::files`]'s sorted order) which was fully committed, and the modification
+/// time it was indexed at.
+///
+/// The `mtime` is persisted alongside the path so a resumed job can sanity-check the file
+/// hasn't changed on disk since it was checkpointed, without needing a separate database
+/// round-trip to find out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobCursor {
+    /// The last file (in sorted order) whose write was fully committed.
+    pub path: PathBuf,
+
+    /// That file's modification time, in seconds since the Unix epoch, at the point it was
+    /// indexed.
+    pub mtime: i64,
+}
+
+/// The persisted state of an indexing job.
+///
+/// This snapshots the full, deterministically sorted list of files discovered for a
+/// workspace up front, and tracks a single [`JobCursor`] marking how far the job has
+/// progressed through that list. This allows a job to be resumed from exactly where it left
+/// off, rather than restarting the whole walk, if the process is interrupted partway through -
+/// as long as [`JobState::advance`] is only ever checkpointed in the same transaction as the
+/// work it marks complete, a crash can never leave the cursor ahead of what was actually
+/// committed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JobState {
+    /// Every file discovered for this job, at the point the job was created, sorted
+    /// deterministically so a [`JobCursor`] position is meaningful across resumes.
+    pub files: Vec<PathBuf>,
+
+    /// How far this job has progressed through [`JobState::files`], if at all.
+    pub cursor: Option<JobCursor>,
+}
+
+impl JobState {
+    /// Create a new job state for a freshly discovered set of files.
+    ///
+    /// `files` must already be sorted - callers resuming this job rely on that order to make
+    /// sense of the persisted [`JobCursor`].
+    #[must_use]
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        Self {
+            files,
+            cursor: None,
+        }
+    }
+
+    /// The index into [`JobState::files`] of the first not-yet-completed file.
+    fn cursor_index(&self) -> usize {
+        match &self.cursor {
+            Some(cursor) => self
+                .files
+                .binary_search(&cursor.path)
+                .map_or(0, |index| index + 1),
+            None => 0,
+        }
+    }
+
+    /// The files which have not yet been processed by this job, in sorted order.
+    pub fn remaining(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().skip(self.cursor_index())
+    }
+
+    /// The files already committed by this job, in sorted order - the complement of
+    /// [`JobState::remaining`].
+    pub fn completed(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().take(self.cursor_index())
+    }
+
+    /// Advance the cursor past `file`, recording its modification time at the point it was
+    /// indexed.
+    ///
+    /// Callers must only persist the advanced state in the same transaction as `file`'s write
+    /// (or once there is nothing left to write for it) - see [`JobState`]'s documentation for
+    /// why that ordering matters.
+    pub fn advance(&mut self, file: &Path, mtime: i64) {
+        self.cursor = Some(JobCursor {
+            path: file.to_path_buf(),
+            mtime,
+        });
+    }
+
+    /// A point-in-time snapshot of how much progress this job has made.
+    #[must_use]
+    pub fn progress(&self) -> Progress {
+        Progress {
+            files_total: self.files.len(),
+            files_completed: self.cursor_index(),
+        }
+    }
+}
+
+/// A point-in-time snapshot of an indexing job's progress, suitable for rendering to a
+/// caller (i.e. a progress bar in an editor).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The total number of files discovered for this job.
+    pub files_total: usize,
+
+    /// The number of files which have been processed so far.
+    pub files_completed: usize,
+}
@@ -5,8 +5,12 @@
 
 mod database_backed_indexer;
 mod error;
+mod job;
+mod rule;
 mod types;
 
 pub use database_backed_indexer::DatabaseBackedIndexer;
 pub use error::Error;
+pub use job::{JobCursor, JobState, JobStatus, Progress};
+pub use rule::{Rule, RuleSet};
 pub use types::*;
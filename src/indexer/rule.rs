@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+/// A rule which controls which files and directories are visited when indexing a workspace,
+/// independent of git's own ignore rules (`.gitignore`/`.git/info/exclude`).
+///
+/// Rules are compiled into a [`RuleSet`], and applied while walking a workspace's directory
+/// tree.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    /// Only files matching one of the provided globs are indexed.
+    ///
+    /// When one or more `AcceptByGlob` rules are present in a [`RuleSet`], a file must match
+    /// at least one of them to be indexed, in addition to passing any `RejectByGlob` rules.
+    AcceptByGlob(Vec<String>),
+
+    /// Files matching one of the provided globs are never indexed.
+    RejectByGlob(Vec<String>),
+
+    /// Any directory (and everything beneath it) whose name matches the provided value is
+    /// pruned entirely from the walk, rather than being filtered file-by-file.
+    ///
+    /// For example, `RejectIfChildOfDirNamed("node_modules".into())` skips the whole
+    /// `node_modules` subtree.
+    RejectIfChildOfDirNamed(String),
+}
+
+/// A compiled, ordered set of [`Rule`]s, ready to be applied while walking a workspace.
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    accept: Option<GlobSet>,
+    reject: GlobSet,
+    rejected_dir_names: Vec<String>,
+}
+
+impl RuleSet {
+    /// Compile an ordered set of rules into a [`RuleSet`].
+    ///
+    /// Invalid globs are logged and skipped, rather than failing the whole rule set.
+    #[must_use]
+    pub fn new(rules: &[Rule]) -> Self {
+        let mut accept_builder = GlobSetBuilder::new();
+        let mut reject_builder = GlobSetBuilder::new();
+        let mut has_accept_rules = false;
+        let mut rejected_dir_names = Vec::new();
+
+        for rule in rules {
+            match rule {
+                Rule::AcceptByGlob(globs) => {
+                    has_accept_rules = true;
+
+                    for glob in globs {
+                        Self::add_glob(&mut accept_builder, glob);
+                    }
+                }
+                Rule::RejectByGlob(globs) => {
+                    for glob in globs {
+                        Self::add_glob(&mut reject_builder, glob);
+                    }
+                }
+                Rule::RejectIfChildOfDirNamed(name) => {
+                    rejected_dir_names.push(name.clone());
+                }
+            }
+        }
+
+        let accept = has_accept_rules.then(|| {
+            accept_builder
+                .build()
+                .expect("Accept globs provided to indexer rules should always be valid")
+        });
+
+        let reject = reject_builder
+            .build()
+            .expect("Reject globs provided to indexer rules should always be valid");
+
+        Self {
+            accept,
+            reject,
+            rejected_dir_names,
+        }
+    }
+
+    fn add_glob(builder: &mut GlobSetBuilder, pattern: &str) {
+        match Glob::new(pattern) {
+            Ok(glob) => {
+                builder.add(glob);
+            }
+            Err(e) => {
+                log::error!("Invalid indexer rule glob ({pattern}), skipping: {e}");
+            }
+        }
+    }
+
+    /// Check whether a directory (and everything beneath it) should be pruned entirely
+    /// from the walk.
+    #[must_use]
+    pub fn is_dir_rejected(&self, path: &Path) -> bool {
+        path.file_name().and_then(|name| name.to_str()).is_some_and(
+            |name| {
+                self.rejected_dir_names
+                    .iter()
+                    .any(|rejected| rejected == name)
+            },
+        )
+    }
+
+    /// Check whether a file passes the configured accept/reject rules.
+    #[must_use]
+    pub fn is_file_accepted(&self, path: &Path) -> bool {
+        if self.reject.is_match(path) {
+            return false;
+        }
+
+        self.accept
+            .as_ref()
+            .is_none_or(|accept| accept.is_match(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Rule, RuleSet};
+
+    #[test]
+    pub fn test_accept_rule_only_allows_matching_files() {
+        let rules = RuleSet::new(&[Rule::AcceptByGlob(vec!["*.rs".to_string()])]);
+
+        assert!(rules.is_file_accepted(&PathBuf::from("src/lib.rs")));
+        assert!(!rules.is_file_accepted(&PathBuf::from("src/lib.go")));
+    }
+
+    #[test]
+    pub fn test_reject_rule_blocks_matching_files() {
+        let rules = RuleSet::new(&[Rule::RejectByGlob(vec!["*.generated.rs".to_string()])]);
+
+        assert!(!rules.is_file_accepted(&PathBuf::from("src/lib.generated.rs")));
+        assert!(rules.is_file_accepted(&PathBuf::from("src/lib.rs")));
+    }
+
+    #[test]
+    pub fn test_no_rules_accepts_everything() {
+        let rules = RuleSet::new(&[]);
+
+        assert!(rules.is_file_accepted(&PathBuf::from("src/lib.rs")));
+    }
+
+    #[test]
+    pub fn test_reject_dir_named() {
+        let rules = RuleSet::new(&[Rule::RejectIfChildOfDirNamed("node_modules".to_string())]);
+
+        assert!(rules.is_dir_rejected(&PathBuf::from("some/project/node_modules")));
+        assert!(!rules.is_dir_rejected(&PathBuf::from("some/project/src")));
+    }
+}
@@ -28,6 +28,57 @@ pub trait Indexer: Send + Sync + Debug {
         &self,
     ) -> impl Future<Output = std::result::Result<(), Vec<indexer::Error>>> + Send;
 
+    /// Index all workspaces registered with the indexer, tracking resumable progress for each
+    /// one (see [`indexer::JobState`]).
+    ///
+    /// Unlike [`Indexer::index_workspaces`], each workspace's walk is checkpointed as it
+    /// proceeds, so if the process is interrupted partway through, the indexer resumes from
+    /// exactly where it left off (see [`indexer::JobStatus::Running`]) the next time it's
+    /// constructed against the same database, rather than re-indexing the whole workspace.
+    ///
+    /// The default implementation just delegates to [`Indexer::index_workspaces`], for
+    /// implementations which have no notion of resumable jobs.
+    fn index_workspaces_with_job(
+        &self,
+    ) -> impl Future<Output = std::result::Result<(), Vec<indexer::Error>>> + Send {
+        self.index_workspaces()
+    }
+
+    /// Resume any `Running` job for each registered workspace, or start a fresh one for a
+    /// workspace that has none.
+    ///
+    /// Unlike [`Indexer::index_workspaces_with_job`], which always starts a brand new job (and
+    /// walk) regardless of what's already in flight, this looks for an incomplete job first
+    /// and continues it from its persisted cursor - the explicit "pick up where indexing left
+    /// off" entry point for a caller that specifically means that, rather than "index from
+    /// scratch".
+    ///
+    /// The default implementation just delegates to [`Indexer::index_workspaces_with_job`],
+    /// for implementations which have no notion of resumable jobs.
+    fn resume_workspaces_with_job(
+        &self,
+    ) -> impl Future<Output = std::result::Result<(), Vec<indexer::Error>>> + Send {
+        self.index_workspaces_with_job()
+    }
+
+    /// Index all workspaces registered with the indexer, using a bounded pool of worker tasks
+    /// to parse files concurrently rather than one at a time (see
+    /// [`indexer::DatabaseBackedIndexer::index_workspace_parallel`]).
+    ///
+    /// `max_concurrency` caps how many files are parsed at once across all workspaces
+    /// combined; pass `None` to default to [`std::thread::available_parallelism`]. Unlike
+    /// [`Indexer::index_workspaces_with_job`], progress is not checkpointed, so this is best
+    /// suited to a cold, first-time index where wall-clock time matters more than resumability.
+    ///
+    /// The default implementation just delegates to [`Indexer::index_workspaces`], for
+    /// implementations which have no notion of bounded concurrency.
+    fn index_workspaces_parallel(
+        &self,
+        _max_concurrency: Option<usize>,
+    ) -> impl Future<Output = std::result::Result<(), Vec<indexer::Error>>> + Send {
+        self.index_workspaces()
+    }
+
     /// Index a particular file, or folder, inside a workspace.
     ///
     /// # Errors
@@ -35,6 +86,17 @@ pub trait Indexer: Send + Sync + Debug {
     /// Returns an error if the folder could not be successfully indexed.
     fn index(&self, path: &Path) -> impl Future<Output = Result<()>> + Send;
 
+    /// Index only the immediate children of a folder, rather than the full subtree.
+    ///
+    /// This is useful for surfacing top-level symbols quickly (i.e. in response to a
+    /// filesystem event), deferring a full recursive [`Indexer::index`] until later.
+    /// If `path` is a file rather than a folder, this behaves identically to `index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder could not be successfully indexed.
+    fn index_shallow(&self, path: &Path) -> impl Future<Output = Result<()>> + Send;
+
     /// De-index a particular file, or folder, in a workspace.
     ///
     /// Usually, this is necessary when a previously indexed file is deleted.
@@ -78,10 +78,13 @@
 //! - [snacks.nvim](https://github.com/folke/snacks.nvim/tree/main) for the excellent picker frontend.
 //! - [frizbee](https://github.com/saghen/frizbee) for the high-performance SIMD implementation of fuzzy matching.
 
+mod change_feed;
 mod utils;
 
 pub mod indexer;
+pub mod lsp;
 pub mod models;
 pub mod parser;
 pub mod resolver;
+pub mod scip;
 pub mod watcher;
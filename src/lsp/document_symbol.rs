@@ -0,0 +1,133 @@
+use crate::{lsp, models::resolved::ResolvedSymbol};
+
+/// Build the nested [`lsp_types::DocumentSymbol`] tree for `textDocument/documentSymbol`, from
+/// every symbol resolved within a single file.
+///
+/// [`ResolvedSymbol::container`] identifies a symbol's enclosing scope by name
+/// (see [`crate::resolver::hierarchy`] for queries built on it), but building a nested tree out
+/// of a flat, single-file symbol list is simplest done by range containment instead: a symbol
+/// becomes the child of the innermost other symbol whose range fully contains it.
+///
+/// `symbols` is expected to all belong to the same file; passing symbols from more than one
+/// file will produce a tree built from their overlapping, unrelated ranges.
+#[must_use]
+pub fn document_symbols(symbols: &[ResolvedSymbol]) -> Vec<lsp_types::DocumentSymbol> {
+    let mut ordered: Vec<&ResolvedSymbol> = symbols.iter().collect();
+
+    // A parent must be visited before its children, so sort by start position first, and break
+    // ties by the widest-spanning symbol first (the one most likely to be the enclosing one).
+    ordered.sort_by_key(|symbol| {
+        (
+            symbol.start_line,
+            symbol.start_column,
+            std::cmp::Reverse(symbol.end_line),
+            std::cmp::Reverse(symbol.end_column),
+        )
+    });
+
+    let mut iter = ordered.into_iter().peekable();
+
+    build_level(&mut iter, None)
+}
+
+/// Consume the symbols nested inside `bound` (or every remaining symbol, if `bound` is
+/// [`Option::None`]) from `iter`, recursing to build up each one's children in turn.
+fn build_level<'a>(
+    iter: &mut std::iter::Peekable<impl Iterator<Item = &'a ResolvedSymbol>>,
+    bound: Option<&ResolvedSymbol>,
+) -> Vec<lsp_types::DocumentSymbol> {
+    let mut level = Vec::new();
+
+    while let Some(&next) = iter.peek() {
+        if bound.is_some_and(|bound| !contains(bound, next)) {
+            break;
+        }
+
+        let symbol = iter.next().expect("just peeked");
+        let mut node = to_document_symbol(symbol);
+
+        let children = build_level(iter, Some(symbol));
+        if !children.is_empty() {
+            node.children = Some(children);
+        }
+
+        level.push(node);
+    }
+
+    level
+}
+
+/// Whether `bound`'s range fully contains `candidate`'s range.
+fn contains(bound: &ResolvedSymbol, candidate: &ResolvedSymbol) -> bool {
+    (bound.start_line, bound.start_column) <= (candidate.start_line, candidate.start_column)
+        && (bound.end_line, bound.end_column) >= (candidate.end_line, candidate.end_column)
+}
+
+#[allow(deprecated)] // `DocumentSymbol::deprecated` itself is deprecated in favour of `tags`.
+fn to_document_symbol(symbol: &ResolvedSymbol) -> lsp_types::DocumentSymbol {
+    lsp_types::DocumentSymbol {
+        name: symbol.name.clone(),
+        detail: None,
+        kind: symbol.kind.into(),
+        tags: None,
+        deprecated: None,
+        // Onoma only tracks a single range per definition, so the selection range (generally
+        // just the symbol's name) and the full range (generally the whole declaration) are the
+        // same.
+        range: lsp::range(symbol),
+        selection_range: lsp::range(symbol),
+        children: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::document_symbols;
+    use std::path::PathBuf;
+
+    use crate::models::{
+        parsed::SymbolKind,
+        resolved::{ResolvedSymbol, Score},
+    };
+
+    fn symbol(name: &str, start_line: i64, end_line: i64) -> ResolvedSymbol {
+        ResolvedSymbol {
+            id: 0,
+            name: name.to_string(),
+            kind: SymbolKind::Function,
+            path: PathBuf::from("/some/file/mod.rs"),
+            container: None,
+            score: Score::default(),
+            start_line,
+            end_line,
+            start_column: 1,
+            end_column: 1,
+        }
+    }
+
+    #[test]
+    fn test_nests_symbols_by_range_containment() {
+        let class = symbol("Client", 1, 10);
+        let method = symbol("fetch", 2, 4);
+
+        let tree = document_symbols(&[class, method]);
+
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].name, "Client");
+
+        let children = tree[0].children.as_ref().expect("Client should have a child");
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "fetch");
+    }
+
+    #[test]
+    fn test_sibling_symbols_stay_flat() {
+        let first = symbol("foo", 1, 2);
+        let second = symbol("bar", 3, 4);
+
+        let tree = document_symbols(&[first, second]);
+
+        assert_eq!(tree.len(), 2);
+        assert!(tree.iter().all(|node| node.children.is_none()));
+    }
+}
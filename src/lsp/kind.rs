@@ -0,0 +1,164 @@
+use crate::models::parsed::SymbolKind;
+
+impl From<SymbolKind> for lsp_types::SymbolKind {
+    /// Collapse Onoma's fine-grained, SCIP-inspired [`SymbolKind`] down to the 26 values LSP
+    /// clients understand.
+    ///
+    /// Many Onoma kinds don't have a dedicated LSP equivalent (e.g. [`SymbolKind::Lemma`] or
+    /// [`SymbolKind::TraitMethod`]), so several are grouped onto the closest LSP kind by what an
+    /// editor would do with them (e.g. jump to it, show its icon), rather than by strict
+    /// semantic equivalence.
+    fn from(kind: SymbolKind) -> Self {
+        match kind {
+            SymbolKind::File => Self::FILE,
+
+            SymbolKind::Module | SymbolKind::Library => Self::MODULE,
+
+            SymbolKind::Namespace | SymbolKind::Lang => Self::NAMESPACE,
+
+            SymbolKind::Package | SymbolKind::PackageObject => Self::PACKAGE,
+
+            SymbolKind::Class
+            | SymbolKind::SingletonClass
+            | SymbolKind::Contract
+            | SymbolKind::Extension => Self::CLASS,
+
+            SymbolKind::Method
+            | SymbolKind::AbstractMethod
+            | SymbolKind::StaticMethod
+            | SymbolKind::ProtocolMethod
+            | SymbolKind::TraitMethod
+            | SymbolKind::MethodAlias
+            | SymbolKind::MethodSpecification
+            | SymbolKind::PureVirtualMethod
+            | SymbolKind::SingletonMethod
+            | SymbolKind::TypeClassMethod => Self::METHOD,
+
+            SymbolKind::Property
+            | SymbolKind::StaticProperty
+            | SymbolKind::Getter
+            | SymbolKind::Setter
+            | SymbolKind::Accessor
+            | SymbolKind::Attribute => Self::PROPERTY,
+
+            SymbolKind::Field | SymbolKind::StaticField | SymbolKind::StaticDataMember => {
+                Self::FIELD
+            }
+
+            SymbolKind::Constructor => Self::CONSTRUCTOR,
+
+            SymbolKind::Enum => Self::ENUM,
+
+            SymbolKind::EnumMember => Self::ENUM_MEMBER,
+
+            SymbolKind::Interface
+            | SymbolKind::Protocol
+            | SymbolKind::Trait
+            | SymbolKind::TypeClass
+            | SymbolKind::Concept
+            | SymbolKind::Mixin
+            | SymbolKind::TypeFamily
+            | SymbolKind::Delegate => Self::INTERFACE,
+
+            SymbolKind::Function
+            | SymbolKind::Macro
+            | SymbolKind::Predicate
+            | SymbolKind::Tactic
+            | SymbolKind::Lemma
+            | SymbolKind::Theorem
+            | SymbolKind::Axiom
+            | SymbolKind::Fact
+            | SymbolKind::Assertion
+            | SymbolKind::Grammar
+            | SymbolKind::Quasiquoter
+            | SymbolKind::DataFamily => Self::FUNCTION,
+
+            SymbolKind::Variable
+            | SymbolKind::SelfParameter
+            | SymbolKind::ThisParameter
+            | SymbolKind::MethodReceiver
+            | SymbolKind::StaticVariable
+            | SymbolKind::Modifier
+            | SymbolKind::Value
+            | SymbolKind::Parameter
+            | SymbolKind::ParameterLabel
+            | SymbolKind::Pattern => Self::VARIABLE,
+
+            SymbolKind::Constant => Self::CONSTANT,
+
+            SymbolKind::String => Self::STRING,
+
+            SymbolKind::Number => Self::NUMBER,
+
+            SymbolKind::Boolean => Self::BOOLEAN,
+
+            SymbolKind::Array => Self::ARRAY,
+
+            SymbolKind::Object | SymbolKind::Instance => Self::OBJECT,
+
+            SymbolKind::Key => Self::KEY,
+
+            SymbolKind::Null | SymbolKind::Unknown => Self::NULL,
+
+            SymbolKind::Struct
+            | SymbolKind::Signature
+            | SymbolKind::Message
+            | SymbolKind::Union
+            | SymbolKind::Error => Self::STRUCT,
+
+            SymbolKind::Event | SymbolKind::StaticEvent => Self::EVENT,
+
+            SymbolKind::Operator | SymbolKind::Subscript => Self::OPERATOR,
+
+            SymbolKind::TypeParameter
+            | SymbolKind::AssociatedType
+            | SymbolKind::Type
+            | SymbolKind::TypeAlias => Self::TYPE_PARAMETER,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapses_method_like_kinds_to_method() {
+        for kind in [
+            SymbolKind::AbstractMethod,
+            SymbolKind::StaticMethod,
+            SymbolKind::ProtocolMethod,
+            SymbolKind::TraitMethod,
+        ] {
+            assert_eq!(lsp_types::SymbolKind::from(kind), lsp_types::SymbolKind::METHOD);
+        }
+    }
+
+    #[test]
+    fn test_collapses_struct_like_kinds_to_struct() {
+        for kind in [SymbolKind::Struct, SymbolKind::Signature, SymbolKind::Message] {
+            assert_eq!(lsp_types::SymbolKind::from(kind), lsp_types::SymbolKind::STRUCT);
+        }
+    }
+
+    #[test]
+    fn test_collapses_proof_like_kinds_to_function_fallback() {
+        for kind in [
+            SymbolKind::Lemma,
+            SymbolKind::Theorem,
+            SymbolKind::Axiom,
+            SymbolKind::Fact,
+        ] {
+            assert_eq!(lsp_types::SymbolKind::from(kind), lsp_types::SymbolKind::FUNCTION);
+        }
+    }
+
+    #[test]
+    fn test_direct_kinds_pass_through() {
+        assert_eq!(lsp_types::SymbolKind::from(SymbolKind::Enum), lsp_types::SymbolKind::ENUM);
+        assert_eq!(
+            lsp_types::SymbolKind::from(SymbolKind::Operator),
+            lsp_types::SymbolKind::OPERATOR
+        );
+    }
+}
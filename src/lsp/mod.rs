@@ -0,0 +1,37 @@
+//! A bridge from Onoma's own [`crate::models`] to the Language Server Protocol, via the
+//! [`lsp_types`] crate.
+//!
+//! This lets Onoma serve `textDocument/documentSymbol` and `workspace/symbol` directly from
+//! resolver results, without every editor integration having to re-derive the same
+//! `SymbolKind` collapsing and tree-building logic.
+
+mod document_symbol;
+mod kind;
+mod workspace_symbol;
+
+pub use document_symbol::document_symbols;
+pub use workspace_symbol::workspace_symbols;
+
+use crate::models::resolved::ResolvedSymbol;
+
+/// Convert a [`ResolvedSymbol`]'s one-based line/column range into a zero-based
+/// [`lsp_types::Range`].
+///
+/// Like [`crate::scip::range_to_scip`], each one-based part is only shifted to zero-based; the
+/// result keeps start and end as LSP [`lsp_types::Position`]s rather than SCIP's flat array.
+///
+/// Columns are passed through as-is. Exact LSP correctness for non-ASCII lines requires the
+/// columns to already be UTF-16 code units (see [`crate::parser::treesitter::LineIndex`]),
+/// which is the caller's responsibility to have used when the symbol was indexed.
+fn range(symbol: &ResolvedSymbol) -> lsp_types::Range {
+    lsp_types::Range::new(
+        lsp_types::Position::new(
+            u32::try_from(symbol.start_line.saturating_sub(1)).unwrap_or(u32::MAX),
+            u32::try_from(symbol.start_column.saturating_sub(1)).unwrap_or(u32::MAX),
+        ),
+        lsp_types::Position::new(
+            u32::try_from(symbol.end_line.saturating_sub(1)).unwrap_or(u32::MAX),
+            u32::try_from(symbol.end_column.saturating_sub(1)).unwrap_or(u32::MAX),
+        ),
+    )
+}
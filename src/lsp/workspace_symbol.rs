@@ -0,0 +1,104 @@
+use crate::{lsp, models::resolved::ResolvedSymbol};
+
+/// Build a flat list of [`lsp_types::WorkspaceSymbol`] values for `workspace/symbol`, from a
+/// Resolver's query results.
+///
+/// Unlike [`crate::lsp::document_symbols`], workspace symbols aren't nested into a tree -
+/// editors show them as a flat, fuzzy-filterable list spanning every file in the workspace, so
+/// Onoma's own scoring and ordering (see [`crate::resolver::Resolver::query`]) is preserved
+/// as-is.
+///
+/// A symbol whose [`ResolvedSymbol::path`] can't be turned into a file [`lsp_types::Url`] (i.e.
+/// it isn't an absolute path) is skipped, since a `WorkspaceSymbol` can't be resolved back to a
+/// location without one.
+#[must_use]
+pub fn workspace_symbols(symbols: &[ResolvedSymbol]) -> Vec<lsp_types::WorkspaceSymbol> {
+    symbols.iter().filter_map(to_workspace_symbol).collect()
+}
+
+fn to_workspace_symbol(symbol: &ResolvedSymbol) -> Option<lsp_types::WorkspaceSymbol> {
+    let uri = lsp_types::Url::from_file_path(&symbol.path).ok()?;
+
+    Some(lsp_types::WorkspaceSymbol {
+        name: symbol.name.clone(),
+        kind: symbol.kind.into(),
+        tags: None,
+        container_name: symbol.container.clone(),
+        location: lsp_types::OneOf::Left(lsp_types::Location::new(uri, lsp::range(symbol))),
+        data: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::workspace_symbols;
+    use crate::models::{
+        parsed::SymbolKind,
+        resolved::{ResolvedSymbol, Score},
+    };
+
+    #[test]
+    fn test_maps_resolved_symbols_with_absolute_paths() {
+        let symbol = ResolvedSymbol {
+            id: 1,
+            name: "fetch".to_string(),
+            kind: SymbolKind::Method,
+            path: PathBuf::from("/some/file/client.rs"),
+            container: None,
+            score: Score::default(),
+            start_line: 2,
+            end_line: 4,
+            start_column: 5,
+            end_column: 10,
+        };
+
+        let workspace_symbols = workspace_symbols(&[symbol]);
+
+        assert_eq!(workspace_symbols.len(), 1);
+        assert_eq!(workspace_symbols[0].name, "fetch");
+        assert_eq!(workspace_symbols[0].kind, lsp_types::SymbolKind::METHOD);
+    }
+
+    #[test]
+    fn test_skips_symbols_with_non_absolute_paths() {
+        let symbol = ResolvedSymbol {
+            id: 1,
+            name: "fetch".to_string(),
+            kind: SymbolKind::Method,
+            path: PathBuf::from("relative/client.rs"),
+            container: None,
+            score: Score::default(),
+            start_line: 2,
+            end_line: 4,
+            start_column: 5,
+            end_column: 10,
+        };
+
+        assert!(workspace_symbols(&[symbol]).is_empty());
+    }
+
+    #[test]
+    fn test_carries_container_through_to_container_name() {
+        let symbol = ResolvedSymbol {
+            id: 1,
+            name: "fetch".to_string(),
+            kind: SymbolKind::Method,
+            path: PathBuf::from("/some/file/client.rs"),
+            container: Some("Client".to_string()),
+            score: Score::default(),
+            start_line: 2,
+            end_line: 4,
+            start_column: 5,
+            end_column: 10,
+        };
+
+        let workspace_symbols = workspace_symbols(&[symbol]);
+
+        assert_eq!(
+            workspace_symbols[0].container_name,
+            Some("Client".to_string())
+        );
+    }
+}
@@ -113,6 +113,11 @@ impl From<Language> for LanguageFn {
 impl Language {
     /// Get the language-specific Treesitter symbol query, in order
     /// to exact all the symbols from a particular source file.
+    ///
+    /// Captures are named after the [`crate::models::parsed::SymbolKind`] they produce (e.g.
+    /// `@Function`), and are tagged as a definition. A capture prefixed with `Reference`
+    /// (e.g. `@ReferenceFunction`) is tagged as a reference instead, so a query can also
+    /// capture call sites and type references, not just where a symbol is defined.
     #[must_use]
     pub const fn get_symbol_query(&self) -> &'static str {
         match self {
@@ -1,5 +1,7 @@
 use std::hash::Hash;
 
+use itertools::Itertools;
+
 use crate::models::{self, parsed::SymbolRole};
 
 /// A symbol which has been parsed out of a source file.
@@ -16,6 +18,15 @@ pub struct Symbol {
     /// Exactly what the name is will depend on what kind of symbol it is.
     pub name: String,
 
+    /// The enclosing type, module, or namespace names this symbol is nested inside, outermost
+    /// first (e.g. `["Client"]` for a method `fetch` on `struct Client`).
+    ///
+    /// This is populated in-memory while parsing (see `extract_symbols`'s ancestor walk), and
+    /// is persisted by the indexer as the `::`-joined path on [`models::resolved::ResolvedSymbol::container`],
+    /// which [`crate::resolver::hierarchy`] uses to answer container/member and call-hierarchy
+    /// queries.
+    pub container: Option<Vec<String>>,
+
     /// The occurrence of the definition of this symbol in the source files.
     pub definition: Option<models::parsed::Occurrence>,
 
@@ -32,11 +43,37 @@ impl Symbol {
         Self {
             kind,
             name: name.to_string(),
+            container: None,
             occurrences: Vec::default(),
             definition: None,
         }
     }
 
+    /// Set the enclosing type, module, or namespace names this symbol is nested inside.
+    #[must_use]
+    pub fn with_container(mut self, container: Vec<String>) -> Self {
+        self.container = Some(container);
+
+        self
+    }
+
+    /// The fully-qualified name of this symbol, joining [`Symbol::container`] and
+    /// [`Symbol::name`] with `::` (e.g. `Client::fetch`).
+    ///
+    /// Falls back to just [`Symbol::name`] when there's no enclosing container.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        self.container.as_ref().map_or_else(
+            || self.name.clone(),
+            |container| {
+                container
+                    .iter()
+                    .chain(std::iter::once(&self.name))
+                    .join("::")
+            },
+        )
+    }
+
     /// Append one of more occurrences from different source files of the symbol.
     pub fn add_occurrence(&mut self, occurrence: models::parsed::Occurrence) {
         if self.definition.is_none() && occurrence.roles.contains(&SymbolRole::Definition) {
@@ -55,6 +92,7 @@ impl Hash for Symbol {
         // different kinds (i.e. method vs function)
 
         self.name.hash(state);
+        self.container.hash(state);
 
         if let Some(definition) = &self.definition {
             definition.hash(state);
@@ -69,6 +107,10 @@ impl PartialEq for Symbol {
         // Don't check the symbol kind here, as its possible we'll see duplicate symbols of
         // different kinds (i.e. method vs function)
 
+        if self.container != other.container {
+            return false;
+        }
+
         if let Some(self_definition) = &self.definition
             && let Some(other_definition) = &other.definition
         {
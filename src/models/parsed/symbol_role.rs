@@ -10,6 +10,10 @@ pub enum SymbolRole {
     /// The occurrence is where the Symbol was defined.
     Definition,
 
+    /// The occurrence is a use of the Symbol elsewhere in the workspace, e.g. a call site
+    /// or a type reference, rather than where it was defined.
+    Reference,
+
     /// A catch-all for any roles not yet promoted to first-class roles.
     Other(String),
 }
@@ -6,7 +6,7 @@ use crate::models;
 
 /// A resolved symbol is a symbol which can been indexed previously (by [`crate::indexer::Indexer`])
 /// and has now been matched to a given query by the Resolver.
-#[derive(Debug, sqlx::FromRow, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, sqlx::FromRow, Eq, PartialEq, Serialize, Deserialize, Clone)]
 pub struct ResolvedSymbol {
     /// The ID of the symbol.
     ///
@@ -37,6 +37,16 @@ pub struct ResolvedSymbol {
     #[sqlx[try_from = "String"]]
     pub path: PathBuf,
 
+    /// The `::`-joined path of types, modules, or namespaces this symbol is nested inside,
+    /// outermost first (e.g. `Client` for a method on `struct Client`, or `Client::Builder`
+    /// for a method nested two levels deep).
+    ///
+    /// `None` for symbols with no enclosing container, or for symbols (e.g. those imported
+    /// from a SCIP index) this hasn't been derived for. See
+    /// [`models::parsed::Symbol::container`] for how it's computed, and
+    /// [`crate::resolver::hierarchy`] for queries built on top of it.
+    pub container: Option<String>,
+
     /// The score is calculated just-in-time by the Resolver and represents a numerical value how
     /// good a match the resolved symbol is for query.
     ///
@@ -71,6 +81,20 @@ pub struct ResolvedSymbol {
     pub end_column: i64,
 }
 
+impl ResolvedSymbol {
+    /// The fully-qualified name of this symbol, joining [`ResolvedSymbol::container`] and
+    /// [`ResolvedSymbol::name`] with `::` (e.g. `Client::fetch`).
+    ///
+    /// Falls back to just [`ResolvedSymbol::name`] when there's no enclosing container.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        self.container.as_deref().map_or_else(
+            || self.name.clone(),
+            |container| format!("{container}::{}", self.name),
+        )
+    }
+}
+
 impl PartialOrd for ResolvedSymbol {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+/// The unit a [`LineIndex`] column is expressed in.
+///
+/// Treesitter reports positions as byte offsets, but editors don't agree on how a "column"
+/// is counted. Over LSP, clients address columns in UTF-16 code units, while a UTF-8-native
+/// caller (or a terminal) generally expects one unit per Unicode scalar value (character).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnEncoding {
+    /// One unit per Unicode scalar value (character).
+    Utf8,
+
+    /// One unit per UTF-16 code unit, as used by the LSP specification - a character outside
+    /// the Basic Multilingual Plane (i.e. one encoded as a UTF-16 surrogate pair) counts as 2.
+    Utf16,
+}
+
+/// A zero-based line/column position within a document, in whichever [`ColumnEncoding`] it was
+/// produced with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn {
+    /// The zero-based line number.
+    pub line: u32,
+
+    /// The zero-based column, in the [`ColumnEncoding`] it was requested in.
+    pub column: u32,
+}
+
+/// A single multi-byte character on a line, recorded so that byte columns can be widened or
+/// narrowed into UTF-8 char or UTF-16 columns without re-scanning the whole line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WideChar {
+    /// The byte offset, relative to the start of the document, of the first byte of the character.
+    start: u32,
+
+    /// The byte offset, relative to the start of the document, one past the last byte of the character.
+    end: u32,
+}
+
+impl WideChar {
+    /// The number of bytes the character occupies when encoded as UTF-8.
+    const fn len_utf8(self) -> u32 {
+        self.end - self.start
+    }
+
+    /// The number of code units the character occupies when encoded as UTF-16 (1, or 2 for a
+    /// surrogate pair).
+    const fn len_utf16(self) -> u32 {
+        if self.len_utf8() == 4 { 2 } else { 1 }
+    }
+}
+
+/// An index over a document's line boundaries, allowing cheap conversion between byte offsets
+/// and line/column positions.
+///
+/// Positions are derived from Treesitter nodes as byte offsets, but editors (and the LSP
+/// specification) address positions as a line and column, with the column counted in UTF-16
+/// code units rather than bytes. Building a `LineIndex` once per document, rather than
+/// re-scanning the document for every symbol, keeps that conversion cheap.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// The byte offset of the start of each line, beginning with `0` for the first line.
+    line_starts: Vec<u32>,
+
+    /// For lines which contain non-ASCII bytes, the multi-byte characters on that line, in
+    /// ascending order of byte offset. Lines entirely made up of ASCII bytes have no entry.
+    wide_chars: HashMap<u32, Vec<WideChar>>,
+}
+
+impl LineIndex {
+    /// Build a `LineIndex` for a document's contents.
+    #[must_use]
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut wide_chars: HashMap<u32, Vec<WideChar>> = HashMap::new();
+
+        let mut line: u32 = 0;
+
+        for (offset, character) in text.char_indices() {
+            let Ok(offset) = u32::try_from(offset) else {
+                break;
+            };
+
+            if character == '\n' {
+                line_starts.push(offset + 1);
+                line += 1;
+
+                continue;
+            }
+
+            let width = u32::try_from(character.len_utf8()).unwrap_or(1);
+
+            if width > 1 {
+                wide_chars.entry(line).or_default().push(WideChar {
+                    start: offset,
+                    end: offset + width,
+                });
+            }
+        }
+
+        Self {
+            line_starts,
+            wide_chars,
+        }
+    }
+
+    /// Convert a byte offset into a document into a zero-based line/column position, with the
+    /// column expressed in the requested [`ColumnEncoding`].
+    ///
+    /// Offsets past the end of the document are clamped to the last line.
+    #[must_use]
+    pub fn to_position(&self, offset: u32, encoding: ColumnEncoding) -> LineColumn {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next_line) => next_line.saturating_sub(1),
+        };
+
+        let line_start = self.line_starts[line];
+        let byte_column = offset.saturating_sub(line_start);
+
+        let column = match (encoding, self.wide_chars.get(&(line as u32))) {
+            (_, None) => byte_column,
+            (ColumnEncoding::Utf8, Some(wide_chars)) => {
+                let extra_bytes: u32 = wide_chars
+                    .iter()
+                    .take_while(|wide_char| wide_char.end <= offset)
+                    .map(|wide_char| wide_char.len_utf8() - 1)
+                    .sum();
+
+                byte_column.saturating_sub(extra_bytes)
+            }
+            (ColumnEncoding::Utf16, Some(wide_chars)) => {
+                let adjustment: u32 = wide_chars
+                    .iter()
+                    .take_while(|wide_char| wide_char.end <= offset)
+                    .map(|wide_char| wide_char.len_utf8() - wide_char.len_utf16())
+                    .sum();
+
+                byte_column.saturating_sub(adjustment)
+            }
+        };
+
+        LineColumn {
+            line: u32::try_from(line).unwrap_or(u32::MAX),
+            column,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnEncoding, LineIndex};
+
+    #[test]
+    pub fn test_position_on_ascii_only_document() {
+        let index = LineIndex::new("fn foo() {}\nfn bar() {}\n");
+
+        // The `b` in `bar`, on the second line
+        let position = index.to_position(15, ColumnEncoding::Utf8);
+
+        assert_eq!(1, position.line);
+        assert_eq!(3, position.column);
+    }
+
+    #[test]
+    pub fn test_utf8_column_counts_characters_not_bytes() {
+        // "caf" + 2-byte "é" + "!"
+        let text = "café!";
+
+        let index = LineIndex::new(text);
+
+        // The byte offset of "!" is 5 (since "é" is 2 bytes), but its char column should be 4
+        let position = index.to_position(5, ColumnEncoding::Utf8);
+
+        assert_eq!(0, position.line);
+        assert_eq!(4, position.column);
+    }
+
+    #[test]
+    pub fn test_utf16_column_counts_surrogate_pairs_as_two() {
+        // An emoji outside the Basic Multilingual Plane, encoded as 4 bytes in UTF-8, and
+        // as a surrogate pair (2 code units) in UTF-16
+        let text = "🎉hello";
+
+        let index = LineIndex::new(text);
+
+        // The byte offset of "h" is 4 (the emoji is 4 bytes in UTF-8), but its UTF-16 column
+        // should be 2 (the emoji is a single surrogate pair)
+        let position = index.to_position(4, ColumnEncoding::Utf16);
+
+        assert_eq!(0, position.line);
+        assert_eq!(2, position.column);
+
+        // For the same offset, the UTF-8 char column should be 1 (the emoji is a single char)
+        let position = index.to_position(4, ColumnEncoding::Utf8);
+
+        assert_eq!(1, position.column);
+    }
+
+    #[test]
+    pub fn test_position_clamps_offsets_past_the_end_of_the_document() {
+        let index = LineIndex::new("fn foo() {}\n");
+
+        let position = index.to_position(9999, ColumnEncoding::Utf8);
+
+        assert_eq!(1, position.line);
+    }
+}
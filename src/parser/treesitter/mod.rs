@@ -6,8 +6,12 @@
 //! The parser _does not_ handle persistence (i.e. building an index). For that capability, refer
 //! to [`crate::indexer`].
 
+mod line_index;
 mod model;
 mod parser;
+mod tree_cache;
 
+pub use line_index::{ColumnEncoding, LineColumn, LineIndex};
 pub use model::*;
 pub use parser::*;
+pub use tree_cache::TreeCache;
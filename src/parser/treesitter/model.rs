@@ -1,23 +1,45 @@
-use crate::models;
+use crate::{models, parser::treesitter::ColumnEncoding};
 
 /// The context in which parsing has begun in.
 ///
 /// Fields defined in here generally help to better inform the parsing behavior, in order
 /// to tailor and improve the output.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Context {
     pub(crate) existing_tree: Option<tree_sitter::Tree>,
+
+    pub(crate) column_encoding: ColumnEncoding,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            existing_tree: None,
+            column_encoding: ColumnEncoding::Utf8,
+        }
+    }
 }
 
 impl Context {
     /// Set an existing tree for the Treesitter query to incrementally update.
-    #[allow(dead_code)]
     #[must_use]
     pub fn with_existing_tree(mut self, tree: tree_sitter::Tree) -> Self {
         self.existing_tree = Some(tree);
 
         self
     }
+
+    /// Set the encoding symbol columns should be reported in.
+    ///
+    /// Defaults to [`ColumnEncoding::Utf8`] (one unit per character). Callers serving an LSP
+    /// client should request [`ColumnEncoding::Utf16`] instead, to match how the protocol
+    /// addresses columns.
+    #[must_use]
+    pub fn with_column_encoding(mut self, column_encoding: ColumnEncoding) -> Self {
+        self.column_encoding = column_encoding;
+
+        self
+    }
 }
 
 /// The output of the parsed source file.
@@ -28,6 +50,5 @@ pub struct Output {
 
     /// The resulting Treesitter tree, which can be used in subsequent calls to
     /// [`crate::parser::treesitter::Parser`] to improve parsing performance.
-    #[allow(dead_code)]
     pub tree: tree_sitter::Tree,
 }
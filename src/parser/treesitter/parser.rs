@@ -41,8 +41,14 @@ impl parser::Parser for Parser {
         let (tree, file_content) =
             Self::parse_file_into_tree(file, &parser_language, ctx.existing_tree.as_ref()).await?;
 
-        let symbols =
-            Self::extract_symbols(file, &file_content, &tree, language, &parser_language)?;
+        let symbols = Self::extract_symbols(
+            file,
+            &file_content,
+            &tree,
+            language,
+            &parser_language,
+            ctx.column_encoding,
+        )?;
 
         let mut index = models::parsed::Index::new(models::parsed::Type::TreeSitter);
 
@@ -97,6 +103,7 @@ impl Parser {
         tree: &tree_sitter::Tree,
         language: models::parsed::Language,
         parser_language: &tree_sitter::Language,
+        column_encoding: super::ColumnEncoding,
     ) -> parser::Result<impl Iterator<Item = models::parsed::Symbol>> {
         let query = tree_sitter::Query::new(parser_language, language.get_symbol_query())
             .map_err(parser::Error::InvalidQuery)?;
@@ -106,13 +113,24 @@ impl Parser {
 
         let capture_names = query.capture_names();
 
+        let line_index = super::LineIndex::new(std::str::from_utf8(file_content).unwrap_or(""));
+
         let mut symbols = Vec::new();
 
         while let Some(m) = matches.next() {
             for c in m.captures {
-                let Ok(kind) =
-                    models::parsed::SymbolKind::from_str(capture_names[c.index as usize])
-                else {
+                let capture_name = capture_names[c.index as usize];
+
+                // Definitions are captured under their bare `SymbolKind` name (e.g.
+                // `Function`), while references to that same kind of symbol elsewhere in
+                // the workspace (e.g. a call site) are captured with a `Reference` prefix
+                // (e.g. `ReferenceFunction`). Everything else is tagged as a definition.
+                let (role, kind_name) = capture_name.strip_prefix("Reference").map_or(
+                    (models::parsed::SymbolRole::Definition, capture_name),
+                    |kind_name| (models::parsed::SymbolRole::Reference, kind_name),
+                );
+
+                let Ok(kind) = models::parsed::SymbolKind::from_str(kind_name) else {
                     continue;
                 };
 
@@ -128,19 +146,30 @@ impl Parser {
 
                 let mut symbol = models::parsed::Symbol::new(kind, &name);
 
-                let start_position = c.node.start_position();
-                let end_position = c.node.end_position();
+                let container = resolve_container(c.node, file_content);
+                if !container.is_empty() {
+                    symbol = symbol.with_container(container);
+                }
+
+                let start = line_index.to_position(
+                    u32::try_from(c.node.start_byte()).unwrap_or_default(),
+                    column_encoding,
+                );
+                let end = line_index.to_position(
+                    u32::try_from(c.node.end_byte()).unwrap_or_default(),
+                    column_encoding,
+                );
 
                 let occurrence = models::parsed::Occurrence::new(
                     language,
                     file,
                     models::parsed::Range::new(
-                        start_position.row + 1,
-                        end_position.row + 1,
-                        start_position.column + 1,
-                        end_position.column + 1,
+                        usize::try_from(start.line).unwrap_or_default() + 1,
+                        usize::try_from(end.line).unwrap_or_default() + 1,
+                        usize::try_from(start.column).unwrap_or_default() + 1,
+                        usize::try_from(end.column).unwrap_or_default() + 1,
                     ),
-                    models::parsed::Roles(vec![models::parsed::SymbolRole::Definition]),
+                    models::parsed::Roles(vec![role]),
                 );
                 symbol.add_occurrence(occurrence);
 
@@ -152,6 +181,60 @@ impl Parser {
     }
 }
 
+/// Tree-sitter node kinds, across the supported grammars, which represent a type, module, or
+/// namespace declaration that other symbols can be nested inside (e.g. a struct, impl block,
+/// class, or module).
+///
+/// This is a coarse, cross-grammar heuristic rather than a per-language table, since exact
+/// container semantics differ subtly between languages; it favours recognising common
+/// declaration shapes over being exhaustive for every grammar.
+fn is_container_kind(kind: &str) -> bool {
+    matches!(
+        kind,
+        "struct_item"
+            | "enum_item"
+            | "impl_item"
+            | "trait_item"
+            | "mod_item"
+            | "type_declaration"
+            | "class_declaration"
+            | "class_specifier"
+            | "interface_declaration"
+            | "namespace_declaration"
+            | "module_declaration"
+    )
+}
+
+/// Walk `node`'s ancestors to find the chain of enclosing container names (e.g. `["Client"]`
+/// for a method nested inside `impl Client`), outermost first.
+///
+/// An ancestor only contributes a name if it's a recognised container node (see
+/// [`is_container_kind`]) and exposes a `name` (or, failing that, `type`) field, which covers
+/// the common shape of a declaration node across the supported grammars. Ancestors that don't
+/// match either are skipped, rather than breaking the walk.
+fn resolve_container(node: tree_sitter::Node<'_>, source: &[u8]) -> Vec<String> {
+    let mut container = Vec::new();
+    let mut current = node.parent();
+
+    while let Some(ancestor) = current {
+        if is_container_kind(ancestor.kind())
+            && let Some(name_node) = ancestor
+                .child_by_field_name("name")
+                .or_else(|| ancestor.child_by_field_name("type"))
+            && let Ok(name) = name_node.utf8_text(source).map(normalise_symbol_name)
+            && !name.is_empty()
+        {
+            container.push(name);
+        }
+
+        current = ancestor.parent();
+    }
+
+    container.reverse();
+
+    container
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
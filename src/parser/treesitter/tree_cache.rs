@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use tree_sitter::{InputEdit, Point, Tree};
+
+use super::Context;
+
+/// A per-file cache of the last parsed buffer and Treesitter tree, used to support
+/// incremental re-parsing.
+///
+/// When a file changes, the previous tree (with an [`InputEdit`] applied to reflect the
+/// byte range that changed) is fed back into the parser, so Treesitter only re-walks the
+/// affected subtree rather than reparsing the whole file from scratch.
+#[derive(Debug, Default)]
+pub struct TreeCache {
+    entries: Mutex<HashMap<PathBuf, CachedFile>>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedFile {
+    content: Vec<u8>,
+    tree: Tree,
+}
+
+impl TreeCache {
+    /// Build a parsing [`Context`] for a file about to be re-parsed.
+    ///
+    /// If a tree is cached for `path`, it is edited to reflect the difference between the
+    /// cached content and `new_content`, and fed back in as the existing tree. Otherwise, a
+    /// default context (no existing tree) is returned, and the file will be parsed from
+    /// scratch.
+    #[must_use]
+    pub fn prepare_context(&self, path: &Path, new_content: &[u8]) -> Context {
+        let cached = self
+            .entries
+            .lock()
+            .expect("Tree cache lock was poisoned")
+            .get(path)
+            .cloned();
+
+        let Some(cached) = cached else {
+            return Context::default();
+        };
+
+        if cached.content == new_content {
+            return Context::default().with_existing_tree(cached.tree);
+        }
+
+        let edit = Self::compute_edit(&cached.content, new_content);
+
+        let mut tree = cached.tree;
+        tree.edit(&edit);
+
+        Context::default().with_existing_tree(tree)
+    }
+
+    /// Store the buffer and tree produced by a successful parse, so they can be reused the
+    /// next time this file changes.
+    pub fn store(&self, path: &Path, content: Vec<u8>, tree: Tree) {
+        self.entries
+            .lock()
+            .expect("Tree cache lock was poisoned")
+            .insert(path.to_path_buf(), CachedFile { content, tree });
+    }
+
+    /// Remove a file's cached tree, i.e. because the file no longer exists.
+    pub fn evict(&self, path: &Path) {
+        self.entries
+            .lock()
+            .expect("Tree cache lock was poisoned")
+            .remove(path);
+    }
+
+    /// Compute the smallest [`InputEdit`] which transforms `old` into `new`, based on the
+    /// common byte prefix and suffix shared by the two buffers.
+    fn compute_edit(old: &[u8], new: &[u8]) -> InputEdit {
+        let common_prefix = old.iter().zip(new.iter()).take_while(|(a, b)| a == b).count();
+
+        let max_common_suffix = (old.len() - common_prefix).min(new.len() - common_prefix);
+        let common_suffix = old[common_prefix..]
+            .iter()
+            .rev()
+            .zip(new[common_prefix..].iter().rev())
+            .take(max_common_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let start_byte = common_prefix;
+        let old_end_byte = old.len() - common_suffix;
+        let new_end_byte = new.len() - common_suffix;
+
+        InputEdit {
+            start_byte,
+            old_end_byte,
+            new_end_byte,
+            start_position: Self::point_at(old, start_byte),
+            old_end_position: Self::point_at(old, old_end_byte),
+            new_end_position: Self::point_at(new, new_end_byte),
+        }
+    }
+
+    /// Compute the row/column [`Point`] for a byte offset into a buffer, by counting
+    /// newlines up to that offset.
+    fn point_at(buffer: &[u8], offset: usize) -> Point {
+        let prefix = &buffer[..offset];
+
+        let row = prefix.iter().filter(|&&byte| byte == b'\n').count();
+
+        let column = prefix
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(prefix.len(), |last_newline| prefix.len() - last_newline - 1);
+
+        Point { row, column }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tree_sitter::Point;
+
+    use super::TreeCache;
+
+    #[test]
+    fn test_compute_edit_for_appended_text() {
+        let old = b"fn foo() {}";
+        let new = b"fn foo() {}\nfn bar() {}";
+
+        let edit = TreeCache::compute_edit(old, new);
+
+        assert_eq!(edit.start_byte, old.len());
+        assert_eq!(edit.old_end_byte, old.len());
+        assert_eq!(edit.new_end_byte, new.len());
+    }
+
+    #[test]
+    fn test_compute_edit_for_inserted_text_in_the_middle() {
+        let old = b"abcxyz";
+        let new = b"abc123xyz";
+
+        let edit = TreeCache::compute_edit(old, new);
+
+        assert_eq!(edit.start_byte, 3);
+        assert_eq!(edit.old_end_byte, 3);
+        assert_eq!(edit.new_end_byte, 6);
+    }
+
+    #[test]
+    fn test_point_at_counts_rows_and_columns() {
+        let buffer = b"abc\ndef\ngh";
+
+        assert_eq!(TreeCache::point_at(buffer, 0), Point { row: 0, column: 0 });
+        assert_eq!(TreeCache::point_at(buffer, 5), Point { row: 1, column: 1 });
+        assert_eq!(TreeCache::point_at(buffer, 9), Point { row: 2, column: 1 });
+    }
+}
@@ -0,0 +1,61 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use tokio_stream::{Stream, wrappers::ReceiverStream};
+use tokio_util::sync::CancellationToken;
+
+use crate::models::resolved::ResolvedSymbol;
+
+/// A [`Stream`] of batches of resolved symbols backed by a detached background query task,
+/// paired with the [`CancellationToken`] that tears that task down.
+///
+/// Symbols are yielded in batches (see [`crate::resolver::constant::QUERY_BATCH_SIZE`]) rather
+/// than one at a time, so a query with a large result set doesn't pay a channel send and an
+/// await per symbol. In [`crate::resolver::StreamMode::Snapshot`] (the default), the stream
+/// closes once the index has been scanned once; in [`crate::resolver::StreamMode::Subscribe`],
+/// it stays open and keeps yielding batches as the index changes, until dropped.
+///
+/// Dropping a bare [`ReceiverStream`] only closes the channel - the spawned task doesn't notice
+/// until its next `send_timeout` call observes the receiver as closed, which can take up to
+/// [`crate::resolver::constant::RESOLVER_SEND_TIMEOUT_SECS`] and holds a pooled `sqlx`
+/// connection open the whole time. `CancellableQuery` instead cancels its token on [`Drop`], so
+/// the task's `tokio::select!` can abort the in-flight query immediately and release the
+/// connection as soon as the caller stops polling.
+#[derive(Debug)]
+pub struct CancellableQuery {
+    stream: ReceiverStream<Vec<ResolvedSymbol>>,
+    token: CancellationToken,
+}
+
+impl CancellableQuery {
+    /// Pair an already-spawned query's receiving stream with the token that cancels it.
+    pub(crate) fn new(
+        stream: ReceiverStream<Vec<ResolvedSymbol>>,
+        token: CancellationToken,
+    ) -> Self {
+        Self { stream, token }
+    }
+
+    /// Clone the underlying cancellation token, so a caller (e.g.
+    /// [`crate::resolver::DatabaseBackedResolver::query_all`]) can cancel this query from
+    /// outside without needing to drop the stream itself.
+    pub(crate) fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+}
+
+impl Stream for CancellableQuery {
+    type Item = Vec<ResolvedSymbol>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+impl Drop for CancellableQuery {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
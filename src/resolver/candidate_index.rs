@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+
+use fst::{
+    Automaton, IntoStreamer, Map, MapBuilder, Streamer,
+    automaton::{Levenshtein, Str, Subsequence},
+};
+
+use crate::resolver::Error;
+
+/// A compact, in-memory candidate index, which prunes the set of symbols a query needs to
+/// be fuzzy-matched and scored against.
+///
+/// This is modeled on rust-analyzer's fst-based symbol index: every symbol contributes its
+/// (lowercased) name, as well as its (lowercased) `path:name`, as keys into an [`fst::Map`],
+/// so that a query can cheaply stream the much smaller set of plausibly-matching symbol ids
+/// out of the finite-state transducer, rather than scoring every indexed symbol.
+///
+/// Symbols whose key collides with another (either because they share a name, or because
+/// both a name and a `path:name` key happen to coincide) are merged into a single postings
+/// list, since `fst::Map` only supports a single `u64` value per key.
+///
+/// Two lookup shapes are supported: [`CandidateIndex::candidates_with_prefix`] for direct,
+/// non-fuzzy exact-prefix matches (e.g. as-you-type completion), and
+/// [`CandidateIndex::candidates`] for typo-tolerant fuzzy matching.
+#[derive(Debug)]
+pub struct CandidateIndex {
+    map: Map<Vec<u8>>,
+    postings: Vec<Vec<i64>>,
+}
+
+impl CandidateIndex {
+    /// Build a candidate index from every indexed symbol's id, name, and defining file path.
+    ///
+    /// `fst::MapBuilder` requires keys to be inserted in strict lexicographic order, so every
+    /// key is collected into a [`BTreeMap`] first; symbols which map to the same key are
+    /// grouped into that key's postings list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying finite-state transducer could not be built.
+    pub fn build<'a>(
+        symbols: impl IntoIterator<Item = (i64, &'a str, &'a str)>,
+    ) -> Result<Self, Error> {
+        let mut grouped: BTreeMap<String, Vec<i64>> = BTreeMap::new();
+
+        for (id, name, path) in symbols {
+            grouped.entry(name.to_lowercase()).or_default().push(id);
+            grouped
+                .entry(format!("{path}:{name}").to_lowercase())
+                .or_default()
+                .push(id);
+        }
+
+        let mut builder = MapBuilder::memory();
+        let mut postings = Vec::with_capacity(grouped.len());
+
+        for (key, ids) in grouped {
+            let value = u64::try_from(postings.len()).unwrap_or(u64::MAX);
+
+            builder.insert(key, value)?;
+            postings.push(ids);
+        }
+
+        let bytes = builder.into_inner()?;
+        let map = Map::new(bytes)?;
+
+        Ok(Self { map, postings })
+    }
+
+    /// Find the bounded set of symbol ids whose indexed key could plausibly match `query`.
+    ///
+    /// A symbol is a candidate if its key is within a Levenshtein edit distance of 1 (for
+    /// queries of 4 characters or fewer) or 2 (for longer queries), or if `query` is a
+    /// subsequence of it. The result is capped at `limit` ids.
+    ///
+    /// Returns `None` if a Levenshtein automaton couldn't be built for `query` (this happens
+    /// for pathologically long queries); callers should fall back to scoring every symbol in
+    /// that case.
+    #[must_use]
+    pub fn candidates(&self, query: &str, limit: usize) -> Option<Vec<i64>> {
+        let query = query.to_lowercase();
+
+        let distance = if query.chars().count() <= 4 { 1 } else { 2 };
+
+        let levenshtein = Levenshtein::new(&query, distance).ok()?;
+        let subsequence = Subsequence::new(&query);
+
+        let automaton = levenshtein.union(subsequence);
+
+        let mut stream = self.map.search(automaton).into_stream();
+
+        let mut ids = Vec::new();
+
+        while let Some((_, value)) = stream.next() {
+            if let Some(postings) = self.postings.get(usize::try_from(value).unwrap_or(usize::MAX))
+            {
+                ids.extend(postings.iter().copied());
+            }
+
+            if ids.len() >= limit {
+                break;
+            }
+        }
+
+        ids.truncate(limit);
+
+        Some(ids)
+    }
+
+    /// Find every symbol id whose indexed key starts with `prefix`.
+    ///
+    /// Unlike [`CandidateIndex::candidates`], this doesn't build a Levenshtein automaton, so
+    /// it's a direct, sub-linear lookup rather than an edit-distance search; it's the fast
+    /// path for exact-prefix queries (e.g. as-you-type completion), falling back to
+    /// [`CandidateIndex::candidates`] for typo-tolerant matching. The result is capped at
+    /// `limit` ids.
+    #[must_use]
+    pub fn candidates_with_prefix(&self, prefix: &str, limit: usize) -> Vec<i64> {
+        let automaton = Str::new(&prefix.to_lowercase()).starts_with();
+
+        let mut stream = self.map.search(automaton).into_stream();
+
+        let mut ids = Vec::new();
+
+        while let Some((_, value)) = stream.next() {
+            if let Some(postings) = self.postings.get(usize::try_from(value).unwrap_or(usize::MAX))
+            {
+                ids.extend(postings.iter().copied());
+            }
+
+            if ids.len() >= limit {
+                break;
+            }
+        }
+
+        ids.truncate(limit);
+
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> CandidateIndex {
+        CandidateIndex::build([
+            (1, "resolve", "x.rs"),
+            (2, "resolver", "x.rs"),
+            (3, "symbol", "x.rs"),
+            (4, "frecency", "x.rs"),
+        ])
+        .expect("index should build from well-formed symbols")
+    }
+
+    #[test]
+    pub fn test_candidates_with_prefix_matches_exact_prefix() {
+        let index = index();
+
+        let ids = index.candidates_with_prefix("resolv", 10);
+
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    pub fn test_candidates_with_prefix_is_case_insensitive() {
+        let index = index();
+
+        let ids = index.candidates_with_prefix("RESOLVER", 10);
+
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    pub fn test_candidates_with_prefix_returns_empty_for_no_match() {
+        let index = index();
+
+        assert!(index.candidates_with_prefix("nonexistent", 10).is_empty());
+    }
+
+    #[test]
+    pub fn test_candidates_with_prefix_respects_limit() {
+        let index = index();
+
+        let ids = index.candidates_with_prefix("resolv", 1);
+
+        assert_eq!(ids.len(), 1);
+    }
+
+    #[test]
+    pub fn test_candidates_tolerates_typo() {
+        let index = index();
+
+        let ids = index
+            .candidates("frecancy", 10)
+            .expect("a Levenshtein automaton should build for a short query");
+
+        assert_eq!(ids, vec![4]);
+    }
+
+    #[test]
+    pub fn test_candidates_matches_subsequence() {
+        let index = index();
+
+        let ids = index
+            .candidates("fcy", 10)
+            .expect("a Levenshtein automaton should build for a short query");
+
+        // "x.rs:frecency" also contains "fcy" as a subsequence, so symbol 4's postings may be
+        // matched (and merged in) via more than one key - only its identity matters here.
+        assert!(!ids.is_empty());
+        assert!(ids.iter().all(|id| *id == 4));
+    }
+
+    #[test]
+    pub fn test_candidates_returns_empty_for_no_match() {
+        let index = index();
+
+        let ids = index
+            .candidates("zzzzzzzzzz", 10)
+            .expect("a Levenshtein automaton should build for this query");
+
+        assert!(ids.is_empty());
+    }
+}
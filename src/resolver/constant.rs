@@ -14,3 +14,24 @@ pub const DEFAULT_SCORE: i64 = 1000;
 /// too long that the Resolving thread is holding a connection in the sqlx pool
 /// and starving future queries from being processed.
 pub const RESOLVER_SEND_TIMEOUT_SECS: u64 = 2;
+
+/// The maximum number of candidate symbol ids a [`crate::resolver::CandidateIndex`] lookup
+/// will surface for a single query, before the bulk of the work moves on to fuzzy matching
+/// and scoring.
+///
+/// This keeps memory flat on huge workspaces, at the cost of (very rarely) missing a match
+/// that would otherwise have been found past this many candidates.
+pub const MAX_CANDIDATE_IDS: usize = 2048;
+
+/// The number of matched, scored symbols a [`crate::resolver::Resolver::query`] accumulates
+/// before flushing them as a batch over the channel, amortizing per-send channel and await
+/// overhead on large result sets.
+///
+/// A non-empty batch is also flushed early, even if it hasn't reached this size, on the next
+/// tick of a fixed [`QUERY_BATCH_FLUSH_INTERVAL_MS`] interval, so a symbol waits at most about
+/// one interval - this keeps latency low on queries with few matches, which would otherwise wait
+/// indefinitely for a batch that's never going to fill up.
+pub const QUERY_BATCH_SIZE: usize = 64;
+
+/// See [`QUERY_BATCH_SIZE`].
+pub const QUERY_BATCH_FLUSH_INTERVAL_MS: u64 = 50;
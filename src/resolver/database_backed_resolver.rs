@@ -1,16 +1,27 @@
-use std::{path::Path, time::Duration};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use itertools::Itertools;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use strum::IntoEnumIterator;
-use tokio::sync::mpsc::{self, error::SendTimeoutError};
+use tokio::sync::{
+    RwLock,
+    mpsc::{self, error::SendTimeoutError},
+};
 use tokio_stream::StreamExt;
 use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
+    change_feed,
     models::{self, resolved::ResolvedSymbol},
     resolver::{
-        Context, Resolver, constant,
+        CancellableQuery, CandidateIndex, Context, Error, QueryCache, ReferenceFilter, Resolver,
+        StreamMode, constant, frecency, hierarchy,
         scoring::{self, fuzzy_match},
     },
     utils::get_database_path,
@@ -21,6 +32,29 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct DatabaseBackedResolver {
     pool: sqlx::Pool<sqlx::Sqlite>,
+
+    /// The path of the database this resolver is connected to, used to look up the
+    /// [`change_feed`] sender a [`crate::indexer::DatabaseBackedIndexer`] against the same
+    /// database publishes to, for [`StreamMode::Subscribe`] queries.
+    database_path: PathBuf,
+
+    /// A lazily-built [`CandidateIndex`], used to prune the set of symbols a query has to
+    /// fuzzy-match and score against.
+    ///
+    /// This is built on first use, and can be rebuilt with [`DatabaseBackedResolver::rebuild_candidate_index`]
+    /// whenever the caller knows the underlying index has changed (for example, after a
+    /// [`crate::watcher::Watcher`] has picked up filesystem changes).
+    candidate_index: Arc<RwLock<Option<CandidateIndex>>>,
+
+    /// Memoizes scored [`Resolver::query`] results, so repeated queries against an unchanged
+    /// index don't re-score from scratch. Invalidated in lockstep with `candidate_index` by
+    /// [`DatabaseBackedResolver::rebuild_candidate_index`].
+    query_cache: Arc<QueryCache>,
+
+    /// The [`CancellationToken`] of whichever [`DatabaseBackedResolver::query_all`] call is
+    /// currently in flight for a given caller-supplied key, so a newer query under the same key
+    /// can cancel it.
+    in_flight_queries: Arc<RwLock<HashMap<String, CancellationToken>>>,
 }
 
 impl DatabaseBackedResolver {
@@ -57,35 +91,445 @@ impl DatabaseBackedResolver {
 
         let pool = SqlitePoolOptions::new().connect_lazy_with(options);
 
-        Self { pool }
+        Self {
+            pool,
+            database_path: PathBuf::from(&database_path),
+            candidate_index: Arc::new(RwLock::new(None)),
+            query_cache: Arc::new(QueryCache::default()),
+            in_flight_queries: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Rebuild the [`CandidateIndex`] from every symbol currently in the database, and
+    /// invalidate every cached [`Resolver::query`] result.
+    ///
+    /// Callers which drive a [`crate::watcher::Watcher`] or [`crate::indexer::Indexer`] against
+    /// the same database should call this after indexing picks up changes, so later queries
+    /// prune against an up-to-date candidate set, and aren't served a stale cached result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried, or the candidate index could not
+    /// be built.
+    pub async fn rebuild_candidate_index(&self) -> std::result::Result<(), Error> {
+        let index = Self::build_candidate_index(&self.pool).await?;
+
+        *self.candidate_index.write().await = Some(index);
+
+        self.query_cache.bump_revision().await;
+
+        Ok(())
+    }
+
+    /// Resolve the symbol directly enclosing `symbol`, if any.
+    ///
+    /// See [`hierarchy::container`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried.
+    pub async fn container(
+        &self,
+        symbol: &ResolvedSymbol,
+    ) -> std::result::Result<Option<ResolvedSymbol>, Error> {
+        hierarchy::container(&self.pool, symbol).await
+    }
+
+    /// Resolve every symbol directly nested inside `container`.
+    ///
+    /// See [`hierarchy::members`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried.
+    pub async fn members(
+        &self,
+        container: &ResolvedSymbol,
+    ) -> std::result::Result<Vec<ResolvedSymbol>, Error> {
+        hierarchy::members(&self.pool, container).await
+    }
+
+    /// Resolve every symbol `symbol` calls.
+    ///
+    /// See [`hierarchy::outgoing_calls`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried.
+    pub async fn outgoing_calls(
+        &self,
+        symbol: &ResolvedSymbol,
+    ) -> std::result::Result<Vec<ResolvedSymbol>, Error> {
+        hierarchy::outgoing_calls(&self.pool, symbol).await
+    }
+
+    /// Resolve every symbol which calls `symbol`.
+    ///
+    /// See [`hierarchy::incoming_calls`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database could not be queried.
+    pub async fn incoming_calls(
+        &self,
+        symbol: &ResolvedSymbol,
+    ) -> std::result::Result<Vec<ResolvedSymbol>, Error> {
+        hierarchy::incoming_calls(&self.pool, symbol).await
+    }
+
+    /// Run a [`Resolver::query`], cancelling whatever query is still in flight under the same
+    /// `key`, if any.
+    ///
+    /// This is meant for callers which re-issue a query on every keystroke (e.g. an editor's
+    /// autocomplete), where only the most recent query's result is ever wanted - without this,
+    /// a burst of stale queries can pile up and starve the connection pool waiting on results
+    /// nobody's going to read.
+    pub async fn query_all(
+        &self,
+        key: impl Into<String>,
+        query: String,
+        ctx: Context,
+    ) -> CancellableQuery {
+        let result = self.query(query, ctx);
+
+        let previous = self
+            .in_flight_queries
+            .write()
+            .await
+            .insert(key.into(), result.token());
+
+        if let Some(previous) = previous {
+            previous.cancel();
+        }
+
+        result
+    }
+
+    /// Flush a batch of scored symbols over the query's channel.
+    ///
+    /// Maintaining a timeout here allows for channels to naturally be closed fairly quickly in
+    /// times of congestion (when many queries are started in quick succession). This is
+    /// important for sqlx, as it has only a small number of open connections in its pool, and
+    /// needlessly waiting for a send to complete here can _easily_ exhaust the available
+    /// connections, and starve newer queries.
+    async fn send_batch(
+        tx: &mpsc::Sender<Vec<ResolvedSymbol>>,
+        batch: Vec<ResolvedSymbol>,
+    ) -> std::result::Result<(), ()> {
+        tx.send_timeout(
+            batch,
+            Duration::from_secs(constant::RESOLVER_SEND_TIMEOUT_SECS),
+        )
+        .await
+        .map_err(|e| match e {
+            SendTimeoutError::Closed(_) => {
+                log::warn!(
+                    "Receiving side of the stream is closed (i.e. no longer waiting for additional batches), stopping task.",
+                );
+            }
+            SendTimeoutError::Timeout(_) => {
+                log::error!(
+                    "Receiving side of the stream was full and sender timed out before delivering a batch"
+                );
+            }
+        })
+    }
+
+    /// After a query's initial scan has drained, stay subscribed to the [`change_feed`] for
+    /// this database and keep pushing batches of newly matching symbols as files are
+    /// re-indexed, until `task_token` is cancelled or the channel closes.
+    ///
+    /// The frecency snapshot is reloaded for each changed file rather than kept from the
+    /// initial scan, since a live subscription can outlive the scan by an arbitrary amount of
+    /// time, and frecency-driven ranking should still reflect accesses recorded in the
+    /// meantime.
+    async fn stream_live_updates(
+        pool: &sqlx::Pool<sqlx::Sqlite>,
+        database_path: &Path,
+        query: &str,
+        ctx: &Context,
+        supported_symbols: &[models::parsed::SymbolKind],
+        tx: &mpsc::Sender<Vec<ResolvedSymbol>>,
+        task_token: &CancellationToken,
+    ) {
+        log::debug!("Query subscribed for live updates: {query}");
+
+        let mut changes = change_feed::sender_for(database_path).subscribe();
+
+        let config = frizbee::Config {
+            max_typos: Some(
+                (u16::try_from(query.len())
+                    .expect("Query length should always be at most 16 unsigned integer")
+                    / 5)
+                .clamp(0, 4),
+            ),
+            sort: false,
+            scoring: frizbee::Scoring::default(),
+        };
+
+        loop {
+            let change = tokio::select! {
+                biased;
+
+                () = task_token.cancelled() => {
+                    log::trace!("Subscribed query cancelled, tearing down");
+
+                    return;
+                }
+                change = changes.recv() => change,
+            };
+
+            let path = match change {
+                Ok(change_feed::Change::Indexed(path)) => path,
+                Ok(change_feed::Change::Deindexed(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Subscribed query missed {skipped} change(s) while catching up");
+
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                    log::debug!("Change feed closed, no more live updates will be pushed");
+
+                    return;
+                }
+            };
+
+            let path_str = path.to_string_lossy();
+
+            let sql_query = format!(
+                r"
+                SELECT
+                    symbol.id,
+                    symbol.kind,
+                    file.path,
+                    symbol.name,
+                    symbol.container,
+                    symbol.start_line,
+                    symbol.end_column,
+                    symbol.end_line,
+                    symbol.start_column
+                FROM symbol
+                    JOIN file ON symbol.file_id = file.id
+                WHERE
+                    file.path = ?
+                    AND symbol.kind IN ({})
+                ",
+                supported_symbols
+                    .iter()
+                    .map(|kind| format!("\"{kind}\""))
+                    .join(",")
+            );
+
+            let rows = match sqlx::query_as::<_, ResolvedSymbol>(&sql_query)
+                .bind(path_str.as_ref())
+                .fetch_all(pool)
+                .await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    log::error!("Failed to re-score changed file {}: {e:?}", path.display());
+
+                    continue;
+                }
+            };
+
+            let frecency_table = match frecency::FrecencyTable::load(pool).await {
+                Ok(table) => table,
+                Err(e) => {
+                    log::error!("Failed to load frecency table, scoring without it: {e:?}");
+
+                    frecency::FrecencyTable::default()
+                }
+            };
+            let now = chrono::Utc::now().timestamp();
+
+            let mut batch = Vec::new();
+
+            for mut symbol in rows {
+                let fuzzy_matches = fuzzy_match(query, &symbol, &config);
+
+                if !query.is_empty() && fuzzy_matches.is_empty() {
+                    continue;
+                }
+
+                let frecency_bonus = frecency_table.score_bonus(
+                    &symbol.path.to_string_lossy(),
+                    &symbol.name,
+                    &symbol.kind.to_string(),
+                    now,
+                );
+
+                symbol.score = scoring::calculate_score(
+                    &symbol,
+                    fuzzy_matches.iter(),
+                    ctx.current_file.as_deref(),
+                    ctx.namespace,
+                    &ctx.heuristics,
+                    &ctx.kind_weights,
+                    Some(frecency_bonus),
+                )
+                .into();
+
+                if *symbol.score < constant::DEFAULT_SCORE {
+                    continue;
+                }
+
+                batch.push(symbol);
+            }
+
+            if !batch.is_empty() && Self::send_batch(tx, batch).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Query every symbol id, name, and defining file path out of the database, and build a
+    /// [`CandidateIndex`] from them.
+    async fn build_candidate_index(
+        pool: &sqlx::Pool<sqlx::Sqlite>,
+    ) -> std::result::Result<CandidateIndex, Error> {
+        let rows = sqlx::query!(
+            r#"
+                SELECT symbol.id, symbol.name, file.path
+                FROM symbol
+                    JOIN file ON symbol.file_id = file.id
+                "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        CandidateIndex::build(
+            rows.iter()
+                .map(|row| (row.id, row.name.as_str(), row.path.as_str())),
+        )
     }
 }
 
 impl Resolver for DatabaseBackedResolver {
     type QueryContext = Context;
 
-    type QueryResult = ReceiverStream<ResolvedSymbol>;
+    type QueryResult = CancellableQuery;
 
     /// Run a query against the indexed Symbols.
     ///
-    /// The query will immediately yield a stream, consisting of resolved symbols
-    /// streamed from the index just-in-time.
+    /// The query will immediately yield a stream of batches of resolved symbols, filled
+    /// just-in-time from the index (see [`constant::QUERY_BATCH_SIZE`]).
+    ///
+    /// In [`StreamMode::Snapshot`] (the default), the stream closes once the index has been
+    /// scanned once. In [`StreamMode::Subscribe`], the stream instead stays open past the
+    /// initial scan and keeps pushing batches of newly matching symbols as the index changes
+    /// (see [`change_feed`]), until the caller stops polling.
     ///
     /// The stream can be dropped at any time, and the resolver will safely cancel
-    /// and shut down the query, even if not all symbols have been returned.
+    /// and shut down the query, even if not all symbols have been returned - dropping the
+    /// returned [`CancellableQuery`] cancels its token immediately, rather than waiting for the
+    /// background task to merely notice the channel closed.
     fn query(&self, query: String, ctx: Self::QueryContext) -> Self::QueryResult {
-        let (tx, rx) = mpsc::channel::<ResolvedSymbol>(100);
+        let (tx, rx) = mpsc::channel::<Vec<ResolvedSymbol>>(100);
 
         let pool = self.pool.clone();
+        let database_path = self.database_path.clone();
+        let candidate_index = Arc::clone(&self.candidate_index);
+        let query_cache = Arc::clone(&self.query_cache);
+
+        let token = CancellationToken::new();
+        let task_token = token.clone();
 
         tokio::spawn(async move {
             log::info!("Executing query: {query}");
 
-            let mut supported_symbols = ctx.symbol_kinds.unwrap_or_default();
+            let mut supported_symbols = ctx.symbol_kinds.clone().unwrap_or_default();
             if supported_symbols.is_empty() {
                 supported_symbols = models::parsed::SymbolKind::iter().collect();
             }
 
+            if let Some(cached) = query_cache.get(&query, &ctx).await {
+                log::debug!(
+                    "Returning {} symbols from the query cache (revision {})",
+                    cached.len(),
+                    query_cache.revision()
+                );
+
+                for batch in cached.chunks(constant::QUERY_BATCH_SIZE) {
+                    if Self::send_batch(&tx, batch.to_vec()).await.is_err() {
+                        return;
+                    }
+                }
+
+                if ctx.stream_mode == StreamMode::Subscribe {
+                    Self::stream_live_updates(
+                        &pool,
+                        &database_path,
+                        &query,
+                        &ctx,
+                        &supported_symbols,
+                        &tx,
+                        &task_token,
+                    )
+                    .await;
+                }
+
+                return;
+            }
+
+            // An empty query means "list everything", so there's no point narrowing the
+            // candidate set first - every symbol is a candidate anyway.
+            let candidate_ids = if query.is_empty() {
+                None
+            } else {
+                if candidate_index.read().await.is_none() {
+                    log::debug!("Candidate index not yet built, building it now");
+
+                    match Self::build_candidate_index(&pool).await {
+                        Ok(index) => *candidate_index.write().await = Some(index),
+                        Err(e) => log::error!("Failed to build candidate index: {e:?}"),
+                    }
+                }
+
+                candidate_index.read().await.as_ref().and_then(|index| {
+                    // `candidates_with_prefix` is a direct, sub-linear lookup, so it's tried
+                    // first; only a query with no exact-prefix matches (e.g. a typo) falls
+                    // through to the more expensive Levenshtein/Subsequence union.
+                    let prefix_matches =
+                        index.candidates_with_prefix(&query, constant::MAX_CANDIDATE_IDS);
+
+                    if prefix_matches.is_empty() {
+                        index.candidates(&query, constant::MAX_CANDIDATE_IDS)
+                    } else {
+                        Some(prefix_matches)
+                    }
+                })
+            };
+
+            if candidate_ids.as_ref().is_some_and(Vec::is_empty) {
+                // The candidate index ruled out every symbol up front - there's nothing left
+                // to fuzzy-match or score.
+                log::info!("Returned 0 symbols (no candidates matched the query).");
+
+                query_cache.insert(&query, &ctx, Vec::new()).await;
+
+                if ctx.stream_mode == StreamMode::Subscribe {
+                    Self::stream_live_updates(
+                        &pool,
+                        &database_path,
+                        &query,
+                        &ctx,
+                        &supported_symbols,
+                        &tx,
+                        &task_token,
+                    )
+                    .await;
+                }
+
+                return;
+            }
+
+            let candidate_clause = candidate_ids
+                .as_ref()
+                .map(|ids| format!("AND symbol.id IN ({})", ids.iter().join(",")))
+                .unwrap_or_default();
+
             let sql_query = format!(
                 r"
                 SELECT
@@ -93,6 +537,7 @@ impl Resolver for DatabaseBackedResolver {
                     symbol.kind,
                     file.path,
                     symbol.name,
+                    symbol.container,
                     symbol.start_line,
                     symbol.end_column,
                     symbol.end_line,
@@ -102,6 +547,7 @@ impl Resolver for DatabaseBackedResolver {
                 WHERE
                     1=1
                     AND symbol.kind IN ({})
+                    {candidate_clause}
                 ",
                 supported_symbols
                     .iter()
@@ -111,7 +557,19 @@ impl Resolver for DatabaseBackedResolver {
 
             let mut results = sqlx::query_as::<_, ResolvedSymbol>(&sql_query).fetch(&pool);
 
+            let frecency_table = match frecency::FrecencyTable::load(&pool).await {
+                Ok(table) => table,
+                Err(e) => {
+                    log::error!("Failed to load frecency table, scoring without it: {e:?}");
+
+                    frecency::FrecencyTable::default()
+                }
+            };
+            let now = chrono::Utc::now().timestamp();
+
             let mut count = 0;
+            let mut cached_results = Vec::new();
+            let mut cancelled = false;
             let config = frizbee::Config {
                 // NOTE: This range must never be below the length of the query, otherwise
                 // frizbee will panic
@@ -125,80 +583,228 @@ impl Resolver for DatabaseBackedResolver {
                 scoring: frizbee::Scoring::default(),
             };
 
-            while let Some(result) = results.next().await {
-                match result {
-                    Ok(mut symbol) => {
-                        let fuzzy_matches = fuzzy_match(&query, &symbol, &config);
+            // Symbols are accumulated here and flushed as a batch, either once
+            // `QUERY_BATCH_SIZE` is reached or the fixed `QUERY_BATCH_FLUSH_INTERVAL_MS`
+            // interval next ticks while the batch is non-empty - whichever comes first. This amortizes
+            // channel send/await overhead on large result sets, while keeping latency low on
+            // queries with few matches.
+            let mut batch: Vec<ResolvedSymbol> = Vec::with_capacity(constant::QUERY_BATCH_SIZE);
+            let mut flush_interval = tokio::time::interval(Duration::from_millis(
+                constant::QUERY_BATCH_FLUSH_INTERVAL_MS,
+            ));
+            flush_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            flush_interval.tick().await; // The first tick fires immediately - consume it upfront.
 
-                        if !query.is_empty() && fuzzy_matches.is_empty() {
-                            // The symbol didn't fuzzy match the query, meaning we can stop here.
-                            continue;
-                        }
+            loop {
+                tokio::select! {
+                    biased;
+
+                    () = task_token.cancelled() => {
+                        log::trace!("Query cancelled, tearing down before results were exhausted");
+
+                        cancelled = true;
+
+                        break;
+                    }
+
+                    _ = flush_interval.tick(), if !batch.is_empty() => {
+                        if Self::send_batch(&tx, std::mem::take(&mut batch)).await.is_err() {
+                            cancelled = true;
 
-                        symbol.score = scoring::calculate_score(
-                            &symbol,
-                            fuzzy_matches.iter(),
-                            ctx.current_file.as_deref(),
-                        )
-                        .into();
-
-                        if *symbol.score < constant::DEFAULT_SCORE {
-                            // The symbol's score is less than the score it started with. This
-                            // indicates that it incurred more penalties than it did bonuses. As
-                            // such, it's likely not a good match.
-                            //
-                            // NB: There is a tradeoff here - in that, a score with penalties
-                            // _might_ still be something a user will want to see. If we find
-                            // there's a lot of "missing" symbols, reevaluating the way in which
-                            // symbols are filtered out of results here would be a good start.
-                            continue;
+                            break;
                         }
+                    }
 
-                        // Maintaining a timeout here allows for channels to naturally be closed
-                        // fairly quickly in times of congestion (when many queries are started
-                        // in quick succession). This is important for sqlx, as it has only a small
-                        // number of open connections in its pool, and needlessly waiting for a
-                        // send to complete here can _easily_ exhaust the available connections, and
-                        // starve newer queries.
-                        if let Err(e) = tx
-                            .send_timeout(
-                                symbol,
-                                Duration::from_secs(constant::RESOLVER_SEND_TIMEOUT_SECS),
-                            )
-                            .await
-                        {
-                            match e {
-                                SendTimeoutError::Closed(_) => {
-                                    log::warn!(
-                                        "Receiving side of the stream is closed (i.e. no longer waiting for additional symbols), stopping task.",
-                                    );
+                    next = results.next() => {
+                        let Some(next) = next else {
+                            break;
+                        };
+
+                        match next {
+                            Ok(mut symbol) => {
+                                let fuzzy_matches = fuzzy_match(&query, &symbol, &config);
+
+                                if !query.is_empty() && fuzzy_matches.is_empty() {
+                                    // The symbol didn't fuzzy match the query, meaning we can stop here.
+                                    continue;
                                 }
-                                SendTimeoutError::Timeout(e) => {
-                                    log::error!(
-                                        "Receiving side of the stream was full and sender timed out before delivering symbol: {e:?}"
-                                    );
+
+                                let frecency_bonus = frecency_table.score_bonus(
+                                    &symbol.path.to_string_lossy(),
+                                    &symbol.name,
+                                    &symbol.kind.to_string(),
+                                    now,
+                                );
+
+                                symbol.score = scoring::calculate_score(
+                                    &symbol,
+                                    fuzzy_matches.iter(),
+                                    ctx.current_file.as_deref(),
+                                    ctx.namespace,
+                                    &ctx.heuristics,
+                                    &ctx.kind_weights,
+                                    Some(frecency_bonus),
+                                )
+                                .into();
+
+                                if *symbol.score < constant::DEFAULT_SCORE {
+                                    // The symbol's score is less than the score it started with. This
+                                    // indicates that it incurred more penalties than it did bonuses. As
+                                    // such, it's likely not a good match.
+                                    //
+                                    // NB: There is a tradeoff here - in that, a score with penalties
+                                    // _might_ still be something a user will want to see. If we find
+                                    // there's a lot of "missing" symbols, reevaluating the way in which
+                                    // symbols are filtered out of results here would be a good start.
+                                    continue;
                                 }
-                            }
 
-                            break;
-                        }
+                                cached_results.push(symbol.clone());
+                                count += 1;
+                                batch.push(symbol);
 
-                        // Symbol returned and the send was successful - we're good to continue
-                        // on.
-                        count += 1;
-                    }
-                    Err(e) => {
-                        log::error!("Error returned from query listing matching symbols: {e}",);
+                                if batch.len() >= constant::QUERY_BATCH_SIZE
+                                    && Self::send_batch(&tx, std::mem::take(&mut batch)).await.is_err()
+                                {
+                                    cancelled = true;
+
+                                    break;
+                                }
+                            }
+                            Err(e) => {
+                                log::error!("Error returned from query listing matching symbols: {e}",);
+                            }
+                        }
                     }
                 }
             }
 
+            if !cancelled && !batch.is_empty() {
+                cancelled = Self::send_batch(&tx, std::mem::take(&mut batch)).await.is_err();
+            }
+
             log::info!(
                 "Returned {count} symbols (until no other symbols left, or stream no longer open)."
             );
+
+            if cancelled {
+                return;
+            }
+
+            query_cache.insert(&query, &ctx, cached_results).await;
+
+            if ctx.stream_mode == StreamMode::Subscribe {
+                Self::stream_live_updates(
+                    &pool,
+                    &database_path,
+                    &query,
+                    &ctx,
+                    &supported_symbols,
+                    &tx,
+                    &task_token,
+                )
+                .await;
+            }
         });
 
-        ReceiverStream::new(rx)
+        CancellableQuery::new(ReceiverStream::new(rx), token)
+    }
+
+    /// Find every occurrence of a symbol, scoped to `language`.
+    ///
+    /// Occurrences are persisted per-file without an explicit language column, so the
+    /// language is instead derived from each occurrence's file path and compared against
+    /// `language`; files whose extension can't be mapped to a [`models::parsed::Language`] are
+    /// skipped.
+    async fn find_references(
+        &self,
+        symbol_name: &str,
+        language: models::parsed::Language,
+        filter: ReferenceFilter,
+    ) -> std::result::Result<Vec<models::parsed::Occurrence>, Error> {
+        let role = match filter {
+            ReferenceFilter::DefinitionsOnly => Some("Definition"),
+            ReferenceFilter::ReferencesOnly => Some("Reference"),
+            ReferenceFilter::All => None,
+        };
+
+        let rows = sqlx::query!(
+            r#"
+                SELECT
+                    occurrence.role,
+                    occurrence.start_line,
+                    occurrence.start_column,
+                    occurrence.end_line,
+                    occurrence.end_column,
+                    file.path
+                FROM occurrence
+                    JOIN file ON occurrence.file_id = file.id
+                WHERE
+                    occurrence.symbol_name = ?
+                    AND (?2 IS NULL OR occurrence.role = ?2)
+                "#,
+            symbol_name,
+            role
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+        let mut occurrences = rows
+            .into_iter()
+            .filter_map(|row| {
+                let path = PathBuf::from(row.path);
+
+                if models::parsed::Language::try_from(path.as_path()).ok() != Some(language) {
+                    return None;
+                }
+
+                let roles = match row.role.as_str() {
+                    "Definition" => models::parsed::Roles(vec![models::parsed::SymbolRole::Definition]),
+                    "Reference" => models::parsed::Roles(vec![models::parsed::SymbolRole::Reference]),
+                    _ => models::parsed::Roles(vec![models::parsed::SymbolRole::Other(row.role)]),
+                };
+
+                let range = models::parsed::Range::new(
+                    usize::try_from(row.start_line).ok()?,
+                    usize::try_from(row.end_line).ok()?,
+                    usize::try_from(row.start_column).ok()?,
+                    usize::try_from(row.end_column).ok()?,
+                );
+
+                Some(models::parsed::Occurrence::new(
+                    language,
+                    path.as_path(),
+                    range,
+                    roles,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        occurrences.sort_unstable();
+
+        Ok(occurrences)
+    }
+
+    /// Record that `symbol` was selected, bumping its recorded frecency.
+    ///
+    /// This also invalidates every cached [`Resolver::query`] result, since frecency isn't part
+    /// of the cache key (see [`QueryCache::key`](query_cache::QueryCache)) - without bumping the
+    /// revision, a cached query would keep returning its pre-access ranking until an unrelated
+    /// index change happened to invalidate it.
+    async fn record_access(&self, symbol: &ResolvedSymbol) -> std::result::Result<(), Error> {
+        frecency::record_access(
+            &self.pool,
+            &symbol.path.to_string_lossy(),
+            &symbol.name,
+            &symbol.kind.to_string(),
+            chrono::Utc::now().timestamp(),
+        )
+        .await?;
+
+        self.query_cache.bump_revision().await;
+
+        Ok(())
     }
 }
 
@@ -213,7 +819,7 @@ mod tests {
     use crate::{
         indexer::{self, Indexer},
         models::{self, parsed::SymbolKind},
-        resolver::Resolver,
+        resolver::{ReferenceFilter, Resolver},
     };
 
     #[tokio::test]
@@ -225,7 +831,7 @@ mod tests {
 
         let workspaces = vec![fixutes.as_path()];
 
-        let indexer = indexer::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone())
+        let indexer = indexer::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
             .await
             .expect("Should be able to create the empty index");
 
@@ -235,8 +841,11 @@ mod tests {
 
         let mut resolved_symbols: Vec<models::resolved::ResolvedSymbol> = resolver
             .query(String::from("func"), super::Context::default())
-            .collect()
-            .await;
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
 
         // The order of symbols is not guaranteed, so we need the sort symbols to keep the
         // snapshot predictable
@@ -257,7 +866,7 @@ mod tests {
 
         let workspaces = vec![fixutes.as_path()];
 
-        let indexer = indexer::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone())
+        let indexer = indexer::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
             .await
             .expect("Should be able to create the empty index");
 
@@ -271,8 +880,11 @@ mod tests {
                 super::Context::default()
                     .with_symbol_kinds(&[SymbolKind::Function, SymbolKind::Method]),
             )
-            .collect()
-            .await;
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
 
         // The order of symbols is not guaranteed, so we need the sort symbols to keep the
         // snapshot predictable
@@ -283,4 +895,151 @@ mod tests {
             {"[].id" => 0} // IDs are non-deterministic, so just blank them out
         );
     }
+
+    #[tokio::test]
+    pub async fn test_finding_references_scoped_by_language() {
+        let storage_path = tempdir()
+            .expect("Should never fail when creating a temporary path for testing indexing");
+
+        let fixutes = PathBuf::from("tests/fixtures/");
+
+        let workspaces = vec![fixutes.as_path()];
+
+        let indexer = indexer::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
+            .await
+            .expect("Should be able to create the empty index");
+
+        let resolver = super::DatabaseBackedResolver::new(storage_path.path(), workspaces.clone());
+
+        assert!(indexer.index_workspaces().await.is_ok());
+
+        let definitions = resolver
+            .find_references(
+                "Point",
+                models::parsed::Language::Rust,
+                ReferenceFilter::DefinitionsOnly,
+            )
+            .await
+            .expect("Should be able to find occurrences of Point");
+
+        assert!(!definitions.is_empty());
+
+        // No reference occurrences are extracted yet, so a references-only filter should
+        // turn up nothing for a symbol which only has a recorded definition.
+        let references = resolver
+            .find_references(
+                "Point",
+                models::parsed::Language::Rust,
+                ReferenceFilter::ReferencesOnly,
+            )
+            .await
+            .expect("Should be able to find occurrences of Point");
+
+        assert!(references.is_empty());
+
+        // A symbol named "Point" defined in the Rust fixture shouldn't be returned when
+        // scoped to a different language.
+        let wrong_language = resolver
+            .find_references(
+                "Point",
+                models::parsed::Language::Go,
+                ReferenceFilter::All,
+            )
+            .await
+            .expect("Should be able to find occurrences of Point");
+
+        assert!(wrong_language.is_empty());
+    }
+
+    #[tokio::test]
+    pub async fn test_resolving_container_and_members() {
+        let storage_path = tempdir()
+            .expect("Should never fail when creating a temporary path for testing indexing");
+
+        let fixutes = PathBuf::from("tests/fixtures/");
+
+        let workspaces = vec![fixutes.as_path()];
+
+        let indexer = indexer::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
+            .await
+            .expect("Should be able to create the empty index");
+
+        let resolver = super::DatabaseBackedResolver::new(storage_path.path(), workspaces.clone());
+
+        assert!(indexer.index_workspaces().await.is_ok());
+
+        let point: Vec<models::resolved::ResolvedSymbol> = resolver
+            .query(
+                String::from("Point"),
+                super::Context::default().with_symbol_kinds(&[SymbolKind::Struct]),
+            )
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .flatten()
+            .collect();
+
+        let point = point
+            .into_iter()
+            .next()
+            .expect("Should have found the Point struct");
+
+        let members = resolver
+            .members(&point)
+            .await
+            .expect("Should be able to resolve Point's members");
+
+        assert!(members.iter().any(|member| member.name == "move_by"));
+
+        let move_by = members
+            .iter()
+            .find(|member| member.name == "move_by")
+            .expect("move_by should be a member of Point")
+            .clone();
+
+        let container = resolver
+            .container(&move_by)
+            .await
+            .expect("Should be able to resolve move_by's container")
+            .expect("move_by should have an enclosing container");
+
+        assert_eq!(container.name, "Point");
+    }
+
+    #[tokio::test]
+    pub async fn test_query_all_cancels_previous_query_under_the_same_key() {
+        let storage_path = tempdir()
+            .expect("Should never fail when creating a temporary path for testing indexing");
+
+        let fixutes = PathBuf::from("tests/fixtures/");
+
+        let workspaces = vec![fixutes.as_path()];
+
+        let indexer = indexer::DatabaseBackedIndexer::new(storage_path.path(), workspaces.clone(), [])
+            .await
+            .expect("Should be able to create the empty index");
+
+        let resolver = super::DatabaseBackedResolver::new(storage_path.path(), workspaces.clone());
+
+        assert!(indexer.index_workspaces().await.is_ok());
+
+        let first = resolver
+            .query_all("completion", String::new(), super::Context::default())
+            .await;
+        let first_token = first.token();
+
+        assert!(
+            !first_token.is_cancelled(),
+            "The first query shouldn't be cancelled until a newer one under the same key arrives"
+        );
+
+        let _second = resolver
+            .query_all("completion", String::new(), super::Context::default())
+            .await;
+
+        assert!(
+            first_token.is_cancelled(),
+            "query_all should cancel the previous in-flight query under the same key"
+        );
+    }
 }
@@ -0,0 +1,14 @@
+use thiserror::Error;
+
+/// Errors that can occur while resolving symbols from, or querying, the index.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// A database error occurred while querying the index.
+    #[error("Database error occurred while resolving: {0}")]
+    QueryFailed(#[from] sqlx::Error),
+
+    /// The finite-state transducer backing a [`crate::resolver::CandidateIndex`] could not be
+    /// built or queried.
+    #[error("Failed to build or query the candidate index: {0}")]
+    CandidateIndexFailed(#[from] fst::Error),
+}
@@ -0,0 +1,178 @@
+//! Tracks how frequently and recently a resolved symbol has been selected (i.e. navigated
+//! to), so that symbols with a history of being useful can be ranked more highly in future
+//! queries.
+//!
+//! The ranking itself is inspired by [zoxide](https://github.com/ajeetdsouza/zoxide)'s
+//! "frecency" algorithm: every recorded access bumps a symbol's `rank`, and a multiplier
+//! derived from how recently it was last accessed is applied on top, so a symbol accessed
+//! once an hour ago can still out-rank one accessed many times last month.
+
+use std::collections::HashMap;
+
+use crate::resolver::{Error, weight};
+
+/// The total summed rank across every tracked symbol above which the table is aged down, so
+/// that symbols which are no longer being navigated to don't permanently inflate every
+/// future query's scoring.
+const RANK_CAP: f64 = 1000.0;
+
+/// The factor every symbol's rank is scaled by once [`RANK_CAP`] is exceeded.
+const AGING_FACTOR: f64 = 0.9;
+
+/// Once a symbol's rank falls below this floor during aging, it's dropped entirely, rather
+/// than being kept around indefinitely with a negligible rank.
+const AGING_FLOOR: f64 = 0.1;
+
+const ONE_HOUR_SECS: i64 = 60 * 60;
+const ONE_DAY_SECS: i64 = ONE_HOUR_SECS * 24;
+const ONE_WEEK_SECS: i64 = ONE_DAY_SECS * 7;
+
+/// A symbol's recorded rank, and the epoch second it was last accessed at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrecencyEntry {
+    /// How many times the symbol has been recorded as accessed (see [`record_access`]),
+    /// scaled down by any aging applied since.
+    pub rank: f64,
+
+    /// The epoch second the symbol was last recorded as accessed.
+    pub last_accessed: i64,
+}
+
+impl FrecencyEntry {
+    /// Compute the frecency multiplier for this entry, relative to `now`.
+    ///
+    /// This blends how often (`rank`) and how recently (`last_accessed`) the symbol has
+    /// been accessed: ×4 within the last hour, ×2 within the last day, ×0.5 within the last
+    /// week, and ×0.25 otherwise.
+    #[must_use]
+    pub fn multiplier(&self, now: i64) -> f64 {
+        let age = now.saturating_sub(self.last_accessed);
+
+        let recency_factor = if age < ONE_HOUR_SECS {
+            4.0
+        } else if age < ONE_DAY_SECS {
+            2.0
+        } else if age < ONE_WEEK_SECS {
+            0.5
+        } else {
+            0.25
+        };
+
+        self.rank * recency_factor
+    }
+}
+
+/// A snapshot of every symbol's recorded frecency, keyed by its identity (path, name, kind).
+///
+/// Unlike [`crate::resolver::CandidateIndex`], this isn't cached across queries - a symbol's
+/// frecency can change on every [`record_access`] call, not just when the index is rebuilt -
+/// so it's loaded fresh by [`FrecencyTable::load`] at the start of each query.
+#[derive(Debug, Default)]
+pub struct FrecencyTable(HashMap<(String, String, String), FrecencyEntry>);
+
+impl FrecencyTable {
+    /// Load every recorded frecency entry from the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying table could not be queried.
+    pub async fn load(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Self, Error> {
+        let rows = sqlx::query!(r#"SELECT path, name, kind, rank, last_accessed FROM symbol_frecency"#)
+            .fetch_all(pool)
+            .await
+            .map_err(Error::QueryFailed)?;
+
+        Ok(Self(
+            rows.into_iter()
+                .map(|row| {
+                    (
+                        (row.path, row.name, row.kind),
+                        FrecencyEntry {
+                            rank: row.rank,
+                            last_accessed: row.last_accessed,
+                        },
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    /// Look up the recorded frecency for a symbol, if any has been recorded yet.
+    #[must_use]
+    pub fn get(&self, path: &str, name: &str, kind: &str) -> Option<FrecencyEntry> {
+        self.0
+            .get(&(path.to_string(), name.to_string(), kind.to_string()))
+            .copied()
+    }
+
+    /// Compute the saturating score bonus for a symbol, in the same per-mille units used by
+    /// every other weight in [`crate::resolver::weight`].
+    ///
+    /// Returns `0` if the symbol has never been recorded as accessed.
+    #[must_use]
+    pub fn score_bonus(&self, path: &str, name: &str, kind: &str, now: i64) -> i64 {
+        self.get(path, name, kind)
+            .map_or(0, |entry| weight::calculate_frecency_score_bonus(entry.multiplier(now)))
+    }
+}
+
+/// Record that a symbol (identified by its defining file path, name, and kind) was selected,
+/// bumping its rank and refreshing its last-accessed time.
+///
+/// The table is aged (see module docs) whenever the summed rank across every tracked symbol
+/// exceeds [`RANK_CAP`], so infrequently accessed symbols naturally fall away over time.
+///
+/// # Errors
+///
+/// Returns an error if the underlying table could not be updated.
+pub async fn record_access(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    path: &str,
+    name: &str,
+    kind: &str,
+    now: i64,
+) -> Result<(), Error> {
+    sqlx::query!(
+        r#"
+            INSERT INTO symbol_frecency (path, name, kind, rank, last_accessed)
+            VALUES (?, ?, ?, 1.0, ?)
+            ON CONFLICT (path, name, kind) DO UPDATE SET
+                rank = rank + 1.0,
+                last_accessed = excluded.last_accessed
+        "#,
+        path,
+        name,
+        kind,
+        now
+    )
+    .execute(pool)
+    .await
+    .map_err(Error::QueryFailed)?;
+
+    age_if_needed(pool).await
+}
+
+/// Scale down every symbol's rank, and drop any which fall below the floor, once the summed
+/// rank across the whole table exceeds the cap.
+async fn age_if_needed(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<(), Error> {
+    let summed = sqlx::query!(r#"SELECT COALESCE(SUM(rank), 0.0) AS total FROM symbol_frecency"#)
+        .fetch_one(pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+    if summed.total.unwrap_or(0.0) <= RANK_CAP {
+        return Ok(());
+    }
+
+    sqlx::query!(r#"UPDATE symbol_frecency SET rank = rank * ?"#, AGING_FACTOR)
+        .execute(pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+    sqlx::query!(r#"DELETE FROM symbol_frecency WHERE rank < ?"#, AGING_FLOOR)
+        .execute(pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+    Ok(())
+}
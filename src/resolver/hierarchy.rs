@@ -0,0 +1,643 @@
+//! Structural queries over already-resolved symbols: containment (who encloses, or is
+//! enclosed by, a symbol) and call hierarchy (who calls, or is called by, a function or
+//! method).
+//!
+//! Unlike [`crate::resolver::Resolver::query`], which fuzzy-matches and scores free-text
+//! queries, these take a [`ResolvedSymbol`] the caller already has in hand (e.g. selected from
+//! a prior query, or under an editor's cursor) and walk the relationships stored alongside it.
+
+use itertools::Itertools;
+
+use crate::{
+    models::{parsed::SymbolKind, resolved::ResolvedSymbol},
+    resolver::Error,
+};
+
+/// `SymbolKind`s which can have members nested inside them (see [`members`]).
+const CONTAINER_KINDS: [SymbolKind; 6] = [
+    SymbolKind::Struct,
+    SymbolKind::Class,
+    SymbolKind::Trait,
+    SymbolKind::Interface,
+    SymbolKind::Enum,
+    SymbolKind::Module,
+];
+
+/// `SymbolKind`s a call-hierarchy node can be built from (see [`incoming_calls`]/[`outgoing_calls`]).
+const CALLABLE_KINDS: [SymbolKind; 2] = [SymbolKind::Function, SymbolKind::Method];
+
+/// Render [`CALLABLE_KINDS`] as a quoted, comma-separated list suitable for a SQL `IN` clause.
+fn callable_kinds_sql() -> String {
+    CALLABLE_KINDS
+        .iter()
+        .map(|kind| format!("\"{kind}\""))
+        .join(",")
+}
+
+/// The columns every query in this module selects, matching [`ResolvedSymbol`]'s fields.
+const SELECT_RESOLVED_SYMBOL_COLUMNS: &str = r"
+    symbol.id,
+    symbol.kind,
+    file.path,
+    symbol.name,
+    symbol.container,
+    symbol.start_line,
+    symbol.end_column,
+    symbol.end_line,
+    symbol.start_column
+";
+
+/// Resolve the symbol directly enclosing `symbol` (its container), if any.
+///
+/// This is the innermost declaration in `symbol.container`'s path (e.g. `Client` for a
+/// method nested inside `impl Client`), re-resolved against the `symbol` table so the
+/// caller gets back a full [`ResolvedSymbol`] rather than just a name.
+///
+/// # Errors
+///
+/// Returns an error if the underlying tables could not be queried.
+pub async fn container(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    symbol: &ResolvedSymbol,
+) -> Result<Option<ResolvedSymbol>, Error> {
+    let Some(container_name) = symbol
+        .container
+        .as_deref()
+        .and_then(|container| container.split("::").next_back())
+    else {
+        return Ok(None);
+    };
+
+    let path = symbol.path.to_string_lossy().into_owned();
+
+    let sql_query = format!(
+        r"
+        SELECT {SELECT_RESOLVED_SYMBOL_COLUMNS}
+        FROM symbol
+            JOIN file ON symbol.file_id = file.id
+        WHERE
+            file.path = ?
+            AND symbol.name = ?
+            AND symbol.start_line <= ?
+            AND symbol.end_line >= ?
+        "
+    );
+
+    let candidates = sqlx::query_as::<_, ResolvedSymbol>(&sql_query)
+        .bind(path)
+        .bind(container_name)
+        .bind(symbol.start_line)
+        .bind(symbol.end_line)
+        .fetch_all(pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+    // The directly enclosing declaration is whichever candidate has the smallest range -
+    // a grandparent container would also satisfy the containment check above, but encloses
+    // a wider range than the immediate parent.
+    Ok(candidates
+        .into_iter()
+        .min_by_key(|candidate| candidate.end_line - candidate.start_line))
+}
+
+/// Resolve every symbol directly nested inside `container` (its members).
+///
+/// Only meaningful for container-like kinds ([`SymbolKind::Struct`], [`SymbolKind::Class`],
+/// [`SymbolKind::Trait`], [`SymbolKind::Interface`], [`SymbolKind::Enum`], or
+/// [`SymbolKind::Module`]) - anything else returns an empty list, since it can't have members.
+///
+/// # Errors
+///
+/// Returns an error if the underlying tables could not be queried.
+pub async fn members(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    container: &ResolvedSymbol,
+) -> Result<Vec<ResolvedSymbol>, Error> {
+    if !CONTAINER_KINDS.contains(&container.kind) {
+        return Ok(Vec::new());
+    }
+
+    let path = container.path.to_string_lossy().into_owned();
+
+    let sql_query = format!(
+        r"
+        SELECT {SELECT_RESOLVED_SYMBOL_COLUMNS}
+        FROM symbol
+            JOIN file ON symbol.file_id = file.id
+        WHERE
+            file.path = ?
+            AND symbol.container IS NOT NULL
+        "
+    );
+
+    let candidates = sqlx::query_as::<_, ResolvedSymbol>(&sql_query)
+        .bind(path)
+        .fetch_all(pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+    // Only keep symbols whose container is `container` itself, by full qualified path - a
+    // symbol nested two levels deep (e.g. `Outer::Inner::method`) is a member of `Inner`, not
+    // `Outer`, and comparing only the innermost segment would also match an unrelated
+    // `Other::Inner` sharing the same trailing name.
+    let qualified_name = container.qualified_name();
+
+    Ok(candidates
+        .into_iter()
+        .filter(|candidate| candidate.container.as_deref() == Some(qualified_name.as_str()))
+        .collect())
+}
+
+/// Resolve every [`SymbolKind::Function`]/[`SymbolKind::Method`] symbol which `symbol` calls
+/// - i.e. every reference occurring within `symbol`'s own definition range which resolves to
+/// another callable symbol in the same file.
+///
+/// Returns an empty list for any symbol which isn't itself callable.
+///
+/// This only resolves calls within the same file `symbol` is defined in - it doesn't yet
+/// follow imports or re-exports to find callees defined elsewhere.
+///
+/// # Errors
+///
+/// Returns an error if the underlying tables could not be queried.
+pub async fn outgoing_calls(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    symbol: &ResolvedSymbol,
+) -> Result<Vec<ResolvedSymbol>, Error> {
+    if !CALLABLE_KINDS.contains(&symbol.kind) {
+        return Ok(Vec::new());
+    }
+
+    let path = symbol.path.to_string_lossy().into_owned();
+
+    let referenced_names = sqlx::query!(
+        r#"
+            SELECT DISTINCT occurrence.symbol_name
+            FROM occurrence
+                JOIN file ON occurrence.file_id = file.id
+            WHERE
+                file.path = ?
+                AND occurrence.role = 'Reference'
+                AND occurrence.start_line >= ?
+                AND occurrence.end_line <= ?
+        "#,
+        path,
+        symbol.start_line,
+        symbol.end_line,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(Error::QueryFailed)?;
+
+    resolve_callables_by_name(
+        pool,
+        &path,
+        referenced_names
+            .iter()
+            .map(|row| row.symbol_name.as_str())
+            .unique(),
+    )
+    .await
+}
+
+/// Resolve every [`SymbolKind::Function`]/[`SymbolKind::Method`] symbol which calls `symbol`
+/// - i.e. every callable symbol in the same file whose definition range contains a reference
+/// to `symbol`'s name.
+///
+/// Returns an empty list for any symbol which isn't itself callable.
+///
+/// This only resolves callers within the same file `symbol` is defined in, for the same
+/// reason [`outgoing_calls`] does.
+///
+/// # Errors
+///
+/// Returns an error if the underlying tables could not be queried.
+pub async fn incoming_calls(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    symbol: &ResolvedSymbol,
+) -> Result<Vec<ResolvedSymbol>, Error> {
+    if !CALLABLE_KINDS.contains(&symbol.kind) {
+        return Ok(Vec::new());
+    }
+
+    let path = symbol.path.to_string_lossy().into_owned();
+
+    let reference_ranges = sqlx::query!(
+        r#"
+            SELECT start_line
+            FROM occurrence
+                JOIN file ON occurrence.file_id = file.id
+            WHERE
+                file.path = ?
+                AND occurrence.symbol_name = ?
+                AND occurrence.role = 'Reference'
+        "#,
+        path,
+        symbol.name,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(Error::QueryFailed)?;
+
+    if reference_ranges.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sql_query = format!(
+        r"
+        SELECT {SELECT_RESOLVED_SYMBOL_COLUMNS}
+        FROM symbol
+            JOIN file ON symbol.file_id = file.id
+        WHERE
+            file.path = ?
+            AND symbol.kind IN ({})
+        ",
+        callable_kinds_sql()
+    );
+
+    let callables = sqlx::query_as::<_, ResolvedSymbol>(&sql_query)
+        .bind(path.as_ref())
+        .fetch_all(pool)
+        .await
+        .map_err(Error::QueryFailed)?;
+
+    Ok(callables
+        .into_iter()
+        .filter(|callable| {
+            reference_ranges.iter().any(|reference| {
+                reference.start_line >= callable.start_line
+                    && reference.start_line <= callable.end_line
+            })
+        })
+        .collect())
+}
+
+/// Resolve a set of symbol names to their [`SymbolKind::Function`]/[`SymbolKind::Method`]
+/// definitions within `path`. No deduplication is done here, so a name passed more than once
+/// yields its symbols more than once.
+async fn resolve_callables_by_name<'a>(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    path: &str,
+    names: impl Iterator<Item = &'a str>,
+) -> Result<Vec<ResolvedSymbol>, Error> {
+    let mut callables = Vec::new();
+
+    let callable_kinds = callable_kinds_sql();
+
+    for name in names {
+        let sql_query = format!(
+            r"
+            SELECT {SELECT_RESOLVED_SYMBOL_COLUMNS}
+            FROM symbol
+                JOIN file ON symbol.file_id = file.id
+            WHERE
+                file.path = ?
+                AND symbol.name = ?
+                AND symbol.kind IN ({callable_kinds})
+            "
+        );
+
+        let mut matches = sqlx::query_as::<_, ResolvedSymbol>(&sql_query)
+            .bind(path)
+            .bind(name)
+            .fetch_all(pool)
+            .await
+            .map_err(Error::QueryFailed)?;
+
+        callables.append(&mut matches);
+    }
+
+    Ok(callables)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use sqlx::sqlite::SqliteConnectOptions;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::models::resolved::Score;
+
+    /// Build a fresh, migrated in-memory-backed database for a test, returning its pool.
+    ///
+    /// This inserts symbols directly via SQL rather than going through a [`crate::parser`],
+    /// so tests can pin down exact ranges and container strings instead of being at the mercy
+    /// of how a real language's grammar happens to nest things.
+    async fn pool() -> sqlx::Pool<sqlx::Sqlite> {
+        let storage_path =
+            tempdir().expect("Should never fail when creating a temporary path for testing");
+
+        let options = SqliteConnectOptions::new()
+            .create_if_missing(true)
+            .filename(storage_path.path().join("test.sqlite3"));
+
+        let pool = sqlx::Pool::connect_lazy_with(options);
+
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("Should be able to run migrations against a fresh database");
+
+        pool
+    }
+
+    /// Insert a file row for `path`, returning its id.
+    async fn insert_file(pool: &sqlx::Pool<sqlx::Sqlite>, path: &str) -> i64 {
+        let now = chrono::Utc::now();
+
+        sqlx::query!(
+            r#"INSERT INTO file (path, indexed_at) VALUES (?, ?) RETURNING id"#,
+            path,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .expect("Should be able to insert a file row")
+        .id
+    }
+
+    /// Insert a symbol row, returning the [`ResolvedSymbol`] it corresponds to.
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_symbol(
+        pool: &sqlx::Pool<sqlx::Sqlite>,
+        file_id: i64,
+        path: &str,
+        kind: SymbolKind,
+        name: &str,
+        container: Option<&str>,
+        start_line: i64,
+        end_line: i64,
+    ) -> ResolvedSymbol {
+        let now = chrono::Utc::now();
+
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO symbol (
+                kind, name, container, file_id, start_line, start_column, end_line, end_column, indexed_at
+            )
+            VALUES (?, ?, ?, ?, ?, 1, ?, 1, ?)
+            RETURNING id
+            "#,
+            kind,
+            name,
+            container,
+            file_id,
+            start_line,
+            end_line,
+            now
+        )
+        .fetch_one(pool)
+        .await
+        .expect("Should be able to insert a symbol row")
+        .id;
+
+        ResolvedSymbol {
+            id,
+            name: name.to_string(),
+            kind,
+            path: PathBuf::from(path),
+            container: container.map(String::from),
+            score: Score::default(),
+            start_line,
+            end_line,
+            start_column: 1,
+            end_column: 1,
+        }
+    }
+
+    /// Insert a `Reference` occurrence row for `symbol_name`, spanning `start_line..=end_line`.
+    async fn insert_reference(
+        pool: &sqlx::Pool<sqlx::Sqlite>,
+        file_id: i64,
+        symbol_name: &str,
+        start_line: i64,
+        end_line: i64,
+    ) {
+        let now = chrono::Utc::now();
+        let role = "Reference";
+
+        sqlx::query!(
+            r#"
+            INSERT INTO occurrence (
+                symbol_name, file_id, role, start_line, start_column, end_line, end_column, indexed_at
+            )
+            VALUES (?, ?, ?, ?, 1, ?, 1, ?)
+            "#,
+            symbol_name,
+            file_id,
+            role,
+            start_line,
+            end_line,
+            now
+        )
+        .execute(pool)
+        .await
+        .expect("Should be able to insert an occurrence row");
+    }
+
+    #[tokio::test]
+    pub async fn test_container_picks_the_smallest_enclosing_range_for_nested_containers() {
+        let pool = pool().await;
+        let file_id = insert_file(&pool, "nested.rs").await;
+
+        // Two symbols named "Inner" both enclose `method`'s range - a wider one (nested two
+        // levels inside "Outer", spanning the whole file) and the actual, tightly-scoped one.
+        // The directly enclosing container is whichever has the smallest range, not whichever
+        // was inserted first.
+        insert_symbol(
+            &pool,
+            file_id,
+            "nested.rs",
+            SymbolKind::Module,
+            "Inner",
+            Some("Outer"),
+            1,
+            20,
+        )
+        .await;
+
+        insert_symbol(
+            &pool,
+            file_id,
+            "nested.rs",
+            SymbolKind::Struct,
+            "Inner",
+            Some("Outer::Inner"),
+            5,
+            15,
+        )
+        .await;
+
+        let method = insert_symbol(
+            &pool,
+            file_id,
+            "nested.rs",
+            SymbolKind::Method,
+            "method",
+            Some("Outer::Inner"),
+            8,
+            10,
+        )
+        .await;
+
+        let resolved = container(&pool, &method)
+            .await
+            .expect("Should be able to resolve method's container")
+            .expect("method should have an enclosing container");
+
+        assert_eq!(resolved.start_line, 5);
+        assert_eq!(resolved.end_line, 15);
+    }
+
+    #[tokio::test]
+    pub async fn test_members_only_matches_the_innermost_container_for_overloaded_names() {
+        let pool = pool().await;
+        let file_id = insert_file(&pool, "overloaded.rs").await;
+
+        // Two distinct containers share the name "Point" in the same file (e.g. one nested
+        // inside a module of the same name) - `members` must only return the member whose
+        // full container path matches the qualified name of the specific `Point` passed in,
+        // not both.
+        let outer_point = insert_symbol(
+            &pool,
+            file_id,
+            "overloaded.rs",
+            SymbolKind::Struct,
+            "Point",
+            None,
+            1,
+            5,
+        )
+        .await;
+
+        insert_symbol(
+            &pool,
+            file_id,
+            "overloaded.rs",
+            SymbolKind::Struct,
+            "Point",
+            Some("shapes::Point"),
+            10,
+            14,
+        )
+        .await;
+
+        insert_symbol(
+            &pool,
+            file_id,
+            "overloaded.rs",
+            SymbolKind::Method,
+            "move_by",
+            Some("Point"),
+            20,
+            22,
+        )
+        .await;
+
+        insert_symbol(
+            &pool,
+            file_id,
+            "overloaded.rs",
+            SymbolKind::Method,
+            "move_by",
+            Some("shapes::Point"),
+            24,
+            26,
+        )
+        .await;
+
+        let members = members(&pool, &outer_point)
+            .await
+            .expect("Should be able to resolve Point's members");
+
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].start_line, 20);
+    }
+
+    #[tokio::test]
+    pub async fn test_outgoing_and_incoming_calls_resolve_each_other() {
+        let pool = pool().await;
+        let file_id = insert_file(&pool, "calls.rs").await;
+
+        let caller = insert_symbol(
+            &pool,
+            file_id,
+            "calls.rs",
+            SymbolKind::Function,
+            "caller",
+            None,
+            1,
+            5,
+        )
+        .await;
+
+        let callee = insert_symbol(
+            &pool,
+            file_id,
+            "calls.rs",
+            SymbolKind::Function,
+            "callee",
+            None,
+            10,
+            12,
+        )
+        .await;
+
+        insert_reference(&pool, file_id, "callee", 3, 3).await;
+
+        let outgoing = outgoing_calls(&pool, &caller)
+            .await
+            .expect("Should be able to resolve caller's outgoing calls");
+
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].name, "callee");
+
+        let incoming = incoming_calls(&pool, &callee)
+            .await
+            .expect("Should be able to resolve callee's incoming calls");
+
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming[0].name, "caller");
+    }
+
+    #[tokio::test]
+    pub async fn test_calls_return_empty_for_a_non_callable_symbol() {
+        let pool = pool().await;
+        let file_id = insert_file(&pool, "field.rs").await;
+
+        let field = insert_symbol(
+            &pool,
+            file_id,
+            "field.rs",
+            SymbolKind::Field,
+            "x",
+            Some("Point"),
+            2,
+            2,
+        )
+        .await;
+
+        assert!(
+            outgoing_calls(&pool, &field)
+                .await
+                .expect("Should be able to query a non-callable symbol")
+                .is_empty()
+        );
+
+        assert!(
+            incoming_calls(&pool, &field)
+                .await
+                .expect("Should be able to query a non-callable symbol")
+                .is_empty()
+        );
+
+        assert!(
+            members(&pool, &field)
+                .await
+                .expect("Should be able to query a non-container symbol")
+                .is_empty()
+        );
+    }
+}
@@ -1,12 +1,24 @@
 //! Tooling for fuzzy matching and scoring symbols from indexes in real-time.
 
+mod candidate_index;
+mod cancellable_query;
 pub(crate) mod constant;
 mod database_backed_resolver;
+mod error;
+pub(crate) mod frecency;
+pub mod hierarchy;
+mod namespace;
+mod query_cache;
 mod scoring;
 mod types;
 mod utils;
 mod weight;
 
+pub use candidate_index::CandidateIndex;
+pub use cancellable_query::CancellableQuery;
 pub use database_backed_resolver::DatabaseBackedResolver;
+pub use error::Error;
+pub use namespace::{Namespace, PerNamespace};
+pub use query_cache::QueryCache;
 
-pub use types::{Context, Resolver};
+pub use types::{Context, ReferenceFilter, Resolver, ScoringConfig, ScoringHeuristics, StreamMode};
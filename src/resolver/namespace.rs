@@ -0,0 +1,162 @@
+use strum_macros::{Display, EnumIter};
+
+use crate::models::{self, parsed::SymbolKind, resolved::ResolvedSymbol};
+
+/// The namespace a symbol occupies.
+///
+/// Many languages allow a type and a value (or a macro) to share the same name without
+/// colliding - for example, Rust's `struct Foo` and `fn foo()` can coexist, as can a
+/// `macro_rules! foo`. Tagging symbols with the namespace they occupy allows a query issued
+/// from a type position (e.g. after a `:`) to prefer the struct `Foo` over the function `foo`,
+/// rather than treating every symbol as occupying a single flat namespace.
+///
+/// This mirrors rust-analyzer's `PerNs` model of `types`, `values`, and `macros` namespaces.
+#[derive(Debug, Display, EnumIter, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub enum Namespace {
+    /// Type-level symbols, such as structs, enums, traits, and interfaces.
+    Types,
+
+    /// Value-level symbols, such as functions, methods, constants, and variables.
+    Values,
+
+    /// Macro definitions.
+    Macros,
+}
+
+impl Namespace {
+    /// Determine which namespace a [`SymbolKind`] occupies, if any.
+    ///
+    /// Symbol kinds which don't map cleanly onto the types/values/macros split (e.g.
+    /// [`SymbolKind::Module`] or [`SymbolKind::Parameter`]) return [`Option::None`], and are
+    /// therefore excluded from namespace-based scoring and grouping.
+    #[must_use]
+    pub const fn of(kind: SymbolKind) -> Option<Self> {
+        match kind {
+            SymbolKind::Struct
+            | SymbolKind::Enum
+            | SymbolKind::Interface
+            | SymbolKind::Trait
+            | SymbolKind::Class
+            | SymbolKind::Type
+            | SymbolKind::TypeAlias
+            | SymbolKind::TypeClass
+            | SymbolKind::TypeParameter
+            | SymbolKind::Union
+            | SymbolKind::Protocol => Some(Self::Types),
+
+            SymbolKind::Function
+            | SymbolKind::Method
+            | SymbolKind::AbstractMethod
+            | SymbolKind::StaticMethod
+            | SymbolKind::Constructor
+            | SymbolKind::Constant
+            | SymbolKind::Variable
+            | SymbolKind::StaticField
+            | SymbolKind::StaticVariable
+            | SymbolKind::StaticProperty
+            | SymbolKind::Field
+            | SymbolKind::EnumMember
+            | SymbolKind::Property => Some(Self::Values),
+
+            SymbolKind::Macro => Some(Self::Macros),
+
+            _ => None,
+        }
+    }
+}
+
+/// The best matching candidate per namespace, analogous to rust-analyzer's `PerNs`.
+///
+/// A query can legitimately return symbols from more than one namespace (e.g. a type
+/// `Foo` and a function `Foo`). Rather than forcing a single winner, `PerNamespace` keeps
+/// the highest scoring candidate from each namespace, so a caller can decide for itself
+/// which (if any) namespaces it cares about.
+#[derive(Debug, Default, Clone)]
+pub struct PerNamespace {
+    /// The best matching symbol in the [`Namespace::Types`] namespace, if any matched.
+    pub types: Option<ResolvedSymbol>,
+
+    /// The best matching symbol in the [`Namespace::Values`] namespace, if any matched.
+    pub values: Option<ResolvedSymbol>,
+
+    /// The best matching symbol in the [`Namespace::Macros`] namespace, if any matched.
+    pub macros: Option<ResolvedSymbol>,
+}
+
+impl PerNamespace {
+    /// Group a set of resolved symbols down to the best (highest scoring) candidate per
+    /// namespace.
+    ///
+    /// Symbols whose kind doesn't map to a [`Namespace`] (see [`Namespace::of`]) are ignored.
+    #[must_use]
+    pub fn from_candidates(candidates: impl IntoIterator<Item = ResolvedSymbol>) -> Self {
+        let mut grouped = Self::default();
+
+        for candidate in candidates {
+            let Some(namespace) = Namespace::of(candidate.kind) else {
+                continue;
+            };
+
+            let slot = match namespace {
+                Namespace::Types => &mut grouped.types,
+                Namespace::Values => &mut grouped.values,
+                Namespace::Macros => &mut grouped.macros,
+            };
+
+            if slot
+                .as_ref()
+                .is_none_or(|existing| candidate.score > existing.score)
+            {
+                *slot = Some(candidate);
+            }
+        }
+
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use models::resolved::Score;
+
+    use super::*;
+
+    fn symbol(kind: SymbolKind, name: &str, score: i64) -> ResolvedSymbol {
+        ResolvedSymbol {
+            id: 1,
+            name: name.to_string(),
+            kind,
+            path: PathBuf::from("some_file.rs"),
+            container: None,
+            score: Score::from(score),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 1,
+        }
+    }
+
+    #[test]
+    pub fn test_namespace_of_common_kinds() {
+        assert_eq!(Some(Namespace::Types), Namespace::of(SymbolKind::Struct));
+        assert_eq!(Some(Namespace::Values), Namespace::of(SymbolKind::Function));
+        assert_eq!(Some(Namespace::Macros), Namespace::of(SymbolKind::Macro));
+        assert_eq!(None, Namespace::of(SymbolKind::Module));
+    }
+
+    #[test]
+    pub fn test_per_namespace_keeps_highest_scoring_candidate() {
+        let grouped = PerNamespace::from_candidates([
+            symbol(SymbolKind::Struct, "Foo", 1000),
+            symbol(SymbolKind::Struct, "Foo", 1200),
+            symbol(SymbolKind::Function, "foo", 1100),
+            symbol(SymbolKind::Module, "foo", 5000),
+        ]);
+
+        assert_eq!(Some(1200), grouped.types.map(|symbol| *symbol.score));
+        assert_eq!(Some(1100), grouped.values.map(|symbol| *symbol.score));
+        assert!(grouped.macros.is_none());
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use tokio::sync::RwLock;
+
+use crate::{models::resolved::ResolvedSymbol, resolver::Context};
+
+/// A monotonically increasing counter, bumped every time the underlying index changes.
+pub type Revision = u64;
+
+/// Caches the scored results of a [`crate::resolver::Resolver::query`] call, keyed on the
+/// query string and whatever in [`Context`] influences scoring, and tagged with the
+/// [`Revision`] the index was at when the result was computed.
+///
+/// This is a coarser approximation of Salsa-style incremental computation: a single
+/// workspace-wide revision rather than per-file input revisions intersected against each
+/// query's candidate set. [`QueryCache::bump_revision`] is called whenever the index changes
+/// (see [`crate::resolver::DatabaseBackedResolver::rebuild_candidate_index`]), which discards
+/// every cached entry in one go, rather than working out which in-flight queries the change
+/// actually affects.
+#[derive(Debug, Default)]
+pub struct QueryCache {
+    revision: AtomicU64,
+    entries: RwLock<HashMap<String, (Revision, Vec<ResolvedSymbol>)>>,
+}
+
+impl QueryCache {
+    /// The index's current revision.
+    pub fn revision(&self) -> Revision {
+        self.revision.load(Ordering::Acquire)
+    }
+
+    /// Record that the underlying index has changed, invalidating every cached entry.
+    pub async fn bump_revision(&self) {
+        self.revision.fetch_add(1, Ordering::AcqRel);
+
+        self.entries.write().await.clear();
+    }
+
+    /// Look up a cached result for `query`/`ctx`, if one was computed at the current revision.
+    ///
+    /// Returns `None` on a miss, whether because nothing's cached for this key, or because
+    /// what's cached was computed at a now-stale revision.
+    pub async fn get(&self, query: &str, ctx: &Context) -> Option<Vec<ResolvedSymbol>> {
+        let revision = self.revision();
+        let entries = self.entries.read().await;
+        let (cached_revision, results) = entries.get(&Self::key(query, ctx))?;
+
+        (*cached_revision == revision).then(|| results.clone())
+    }
+
+    /// Cache `results` for `query`/`ctx`, tagged with the index's current revision.
+    pub async fn insert(&self, query: &str, ctx: &Context, results: Vec<ResolvedSymbol>) {
+        let revision = self.revision();
+
+        self.entries
+            .write()
+            .await
+            .insert(Self::key(query, ctx), (revision, results));
+    }
+
+    /// Build a cache key out of the parts of a query which affect its scored result: the
+    /// query string itself, plus whatever in `ctx` feeds into filtering or scoring.
+    fn key(query: &str, ctx: &Context) -> String {
+        format!(
+            "{query}|{:?}|{:?}|{:?}|{:?}|{:?}",
+            ctx.current_file, ctx.symbol_kinds, ctx.namespace, ctx.kind_weights, ctx.heuristics
+        )
+    }
+}
@@ -2,7 +2,9 @@ use std::{ffi::OsStr, path::Path};
 
 use crate::{
     models::{self},
-    resolver::{constant::DEFAULT_SCORE, utils, weight},
+    resolver::{
+        Namespace, ScoringConfig, ScoringHeuristics, constant::DEFAULT_SCORE, utils, weight,
+    },
 };
 
 /// Run fuzzy matching on a given symbol, for a query, using a set of configuration.
@@ -43,10 +45,23 @@ pub fn fuzzy_match(
 /// The default score, if no bonuses or penalties are applied is defined as [`constant::DEFAULT_SCORE`].
 /// Any score returned which is _below_ the default can be assumed to have occurred more penalties
 /// than bonuses, and thus not a good match.
+///
+/// `frecency_bonus` is an optional, pre-computed bonus (see
+/// [`crate::resolver::frecency::FrecencyTable::score_bonus`]) reflecting how frequently and
+/// recently this symbol has previously been selected by a caller. It's computed outside of
+/// this function since looking it up requires a database query, which this function can't
+/// perform itself.
+///
+/// `kind_weights` supplies any per-[`models::parsed::SymbolKind`] score overrides (see
+/// [`ScoringConfig`]), layered over [`weight::default_kind_weight`].
 pub fn calculate_score<'a, 'b>(
     symbol: &models::resolved::ResolvedSymbol,
     fuzzy_matches: impl Iterator<Item = &'a neo_frizbee::Match>,
     current_file: Option<&'b Path>,
+    expected_namespace: Option<Namespace>,
+    heuristics: &ScoringHeuristics,
+    kind_weights: &ScoringConfig,
+    frecency_bonus: Option<i64>,
 ) -> i64 {
     let filename = if let Some(Some(filename)) = symbol.path.file_name().map(OsStr::to_str) {
         Some(filename)
@@ -55,46 +70,38 @@ pub fn calculate_score<'a, 'b>(
     };
 
     let entrypoint_file_penalty = if let Some(filename) = filename
-        && utils::is_entrypoint_file(filename)
+        && utils::is_entrypoint_file(filename, &heuristics.entrypoint_filenames)
     {
         // 1% penalty for symbols defined in an entrypoint - this helps to
         // filter out re-exports
-        weight::ENTRYPOINT_FILE_SCORE_PENALTY
+        heuristics
+            .entrypoint_penalty
+            .unwrap_or(weight::ENTRYPOINT_FILE_SCORE_PENALTY)
     } else {
         0
     };
 
     let fuzzy_match_bonus: i64 = fuzzy_matches.map(weight::calculate_fuzzy_match_bonus).sum();
 
-    let symbol_kind_bonus = match symbol.kind {
-        // 3.5% bonus for the most common symbol kinds
-        models::parsed::SymbolKind::Function
-        | models::parsed::SymbolKind::Method
-        | models::parsed::SymbolKind::Struct
-        | models::parsed::SymbolKind::Type
-        | models::parsed::SymbolKind::TypeAlias
-        | models::parsed::SymbolKind::Class
-        | models::parsed::SymbolKind::Constant
-        | models::parsed::SymbolKind::Enum
-        | models::parsed::SymbolKind::EnumMember
-        | models::parsed::SymbolKind::Interface => weight::COMMON_SYMBOL_KINDS_SCORE_BONUS,
-
-        // 0.5% bonus for less frequently but helpful symbol kinds
-        models::parsed::SymbolKind::Variable => weight::INFREQUENT_SYMBOL_KINDS_SCORE_BONUS,
-
-        // 1.5% PENALTY for uncommon symbols
-        models::parsed::SymbolKind::Package
-        | models::parsed::SymbolKind::Module
-        | models::parsed::SymbolKind::SelfParameter => weight::UNCOMMON_SYMBOL_KINDS_SCORE_PENALTY,
-
-        // No bonus for any other kinds
-        _ => 0,
-    };
-
-    let test_harness_penalty = if utils::is_part_of_test_harness(symbol.path.as_path()) {
+    // A signed per-kind delta (e.g. a bonus for top-level declarations like `Function` or
+    // `Struct`, a penalty for locals and sub-symbols like `Parameter`), so a match against a
+    // kind a user is unlikely to be searching for by name doesn't out-rank one they are. An
+    // explicit override in `kind_weights` always wins over the built-in default.
+    let symbol_kind_bonus = kind_weights
+        .kind_weights
+        .get(&symbol.kind)
+        .copied()
+        .unwrap_or_else(|| weight::default_kind_weight(symbol.kind));
+
+    let test_harness_penalty = if utils::is_part_of_test_harness(
+        symbol.path.as_path(),
+        &heuristics.test_file_patterns,
+    ) {
         // 0.5% penalty for symbols which are part of a test harness (i.e. it's likely a test
         // case, part of a test file, etc.)
-        weight::TEST_HARNESS_SCORE_PENALTY
+        heuristics
+            .test_harness_penalty
+            .unwrap_or(weight::TEST_HARNESS_SCORE_PENALTY)
     } else {
         0
     };
@@ -107,12 +114,27 @@ pub fn calculate_score<'a, 'b>(
         ))
     });
 
+    // 2% bonus/penalty depending on whether the symbol's namespace (if it has one) matches
+    // the namespace the query was issued from. Symbols whose kind doesn't map to a namespace
+    // (see `Namespace::of`) are left unaffected.
+    let namespace_adjustment = expected_namespace.map_or(0, |expected_namespace| {
+        match Namespace::of(symbol.kind) {
+            Some(namespace) if namespace == expected_namespace => {
+                weight::NAMESPACE_MATCH_SCORE_BONUS
+            }
+            Some(_) => weight::NAMESPACE_MISMATCH_SCORE_PENALTY,
+            None => 0,
+        }
+    });
+
     DEFAULT_SCORE
         .saturating_add(entrypoint_file_penalty)
         .saturating_add(fuzzy_match_bonus)
         .saturating_add(symbol_kind_bonus)
         .saturating_add(test_harness_penalty)
         .saturating_add(distance_penalty)
+        .saturating_add(namespace_adjustment)
+        .saturating_add(frecency_bonus.unwrap_or(0))
 }
 
 #[cfg(test)]
@@ -124,7 +146,7 @@ mod tests {
             parsed::SymbolKind,
             resolved::{ResolvedSymbol, Score},
         },
-        resolver::scoring::DEFAULT_SCORE,
+        resolver::{ScoringConfig, ScoringHeuristics, scoring::DEFAULT_SCORE},
     };
 
     #[test]
@@ -134,6 +156,7 @@ mod tests {
             name: "ResolvedSymbol".to_string(),
             kind: SymbolKind::Struct,
             path: PathBuf::from("/some/file/mod.rs"),
+            container: None,
             score: Score::default(),
             start_line: 1,
             start_column: 1,
@@ -141,7 +164,15 @@ mod tests {
             end_column: 14,
         };
 
-        let score = super::calculate_score(&symbol, Vec::new().iter(), None);
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            None,
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
 
         let mut target_score = DEFAULT_SCORE;
 
@@ -158,6 +189,7 @@ mod tests {
             name: "ResolvedSymbol".to_string(),
             kind: SymbolKind::Struct,
             path: PathBuf::from("/some/file"),
+            container: None,
             score: Score::default(),
             start_line: 1,
             start_column: 1,
@@ -165,7 +197,15 @@ mod tests {
             end_column: 14,
         };
 
-        let score = super::calculate_score(&symbol, Vec::new().iter(), None);
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            None,
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
 
         let mut target_score = DEFAULT_SCORE;
 
@@ -183,6 +223,7 @@ mod tests {
             name: "ResolvedSymbol".to_string(),
             kind: SymbolKind::Variable,
             path: PathBuf::from_iter(["", "some", "file", "over", "here", "file.rs"]),
+            container: None,
             score: Score::default(),
             start_line: 1,
             start_column: 1,
@@ -202,6 +243,10 @@ mod tests {
                 "there",
                 "file.ts",
             ])),
+            None,
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
         );
 
         let mut target_score = DEFAULT_SCORE;
@@ -219,6 +264,7 @@ mod tests {
             name: "tests".to_string(),
             kind: SymbolKind::Module,
             path: PathBuf::from("some_module.rs"),
+            container: None,
             score: Score::default(),
             start_line: 1,
             start_column: 1,
@@ -226,11 +272,19 @@ mod tests {
             end_column: 14,
         };
 
-        let score = super::calculate_score(&symbol, Vec::new().iter(), None);
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            None,
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
 
         let mut target_score = DEFAULT_SCORE;
 
-        target_score -= 15; // Decrease the score by 0.5%, because it is a variable
+        target_score += 35; // Increase the score by 3.5%, because it is a top-level module
 
         assert_eq!(target_score, score);
     }
@@ -242,6 +296,7 @@ mod tests {
             name: "TestClass".to_string(),
             kind: SymbolKind::Class,
             path: PathBuf::from("some_file.test.ts"),
+            container: None,
             score: Score::default(),
             start_line: 1,
             start_column: 1,
@@ -249,7 +304,15 @@ mod tests {
             end_column: 9,
         };
 
-        let score = super::calculate_score(&symbol, Vec::new().iter(), None);
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            None,
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
 
         let mut target_score = DEFAULT_SCORE;
 
@@ -271,6 +334,7 @@ mod tests {
             name: name.clone(),
             kind: SymbolKind::Lemma,
             path: path.clone(),
+            container: None,
             score: Score::default(),
             start_line: 1,
             start_column: 1,
@@ -297,7 +361,15 @@ mod tests {
             &config,
         );
 
-        let score = super::calculate_score(&symbol, fuzzy_matches.iter(), None);
+        let score = super::calculate_score(
+            &symbol,
+            fuzzy_matches.iter(),
+            None,
+            None,
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
 
         let mut target_score = DEFAULT_SCORE;
 
@@ -305,4 +377,137 @@ mod tests {
 
         assert_eq!(target_score, score);
     }
+
+    #[test]
+    pub fn test_scoring_symbol_matching_expected_namespace() {
+        let symbol = ResolvedSymbol {
+            id: 1,
+            name: "Foo".to_string(),
+            kind: SymbolKind::Struct,
+            path: PathBuf::from("some_file.rs"),
+            container: None,
+            score: Score::default(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 3,
+        };
+
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            Some(crate::resolver::Namespace::Types),
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
+
+        let mut target_score = DEFAULT_SCORE;
+
+        target_score += 35; // Increase the score by 3.5%, because it is a struct
+        target_score += 20; // Increase the score by 2%, because it's in the expected namespace
+
+        assert_eq!(target_score, score);
+    }
+
+    #[test]
+    pub fn test_scoring_symbol_mismatching_expected_namespace() {
+        let symbol = ResolvedSymbol {
+            id: 1,
+            name: "foo".to_string(),
+            kind: SymbolKind::Function,
+            path: PathBuf::from("some_file.rs"),
+            container: None,
+            score: Score::default(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 3,
+        };
+
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            Some(crate::resolver::Namespace::Types),
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
+
+        let mut target_score = DEFAULT_SCORE;
+
+        target_score += 35; // Increase the score by 3.5%, because it is a function
+        target_score -= 20; // Decrease the score by 2%, because it's not in the expected namespace
+
+        assert_eq!(target_score, score);
+    }
+
+    #[test]
+    pub fn test_scoring_parameter_is_penalized_by_default() {
+        let symbol = ResolvedSymbol {
+            id: 1,
+            name: "foo".to_string(),
+            kind: SymbolKind::Parameter,
+            path: PathBuf::from("some_file.rs"),
+            container: None,
+            score: Score::default(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 3,
+        };
+
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            None,
+            &ScoringHeuristics::default(),
+            &ScoringConfig::default(),
+            None,
+        );
+
+        let mut target_score = DEFAULT_SCORE;
+
+        target_score -= 30; // Decrease the score by 3%, because it's a local/sub-symbol
+
+        assert_eq!(target_score, score);
+    }
+
+    #[test]
+    pub fn test_scoring_config_overrides_default_kind_weight() {
+        // Lean's top-level declarations (theorems, lemmas) otherwise have no dedicated weight.
+        let symbol = ResolvedSymbol {
+            id: 1,
+            name: "foo".to_string(),
+            kind: SymbolKind::Lemma,
+            path: PathBuf::from("some_file.lean"),
+            container: None,
+            score: Score::default(),
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 3,
+        };
+
+        let kind_weights = ScoringConfig::default().with_kind_weight(SymbolKind::Lemma, 35);
+
+        let score = super::calculate_score(
+            &symbol,
+            Vec::new().iter(),
+            None,
+            None,
+            &ScoringHeuristics::default(),
+            &kind_weights,
+            None,
+        );
+
+        let mut target_score = DEFAULT_SCORE;
+
+        target_score += 35; // Increase the score by 3.5%, per the overridden kind weight
+
+        assert_eq!(target_score, score);
+    }
 }
@@ -3,19 +3,16 @@ use std::{fmt::Debug, path::PathBuf};
 #[cfg(test)]
 use mockall::{automock, predicate::*};
 
+use crate::models::{self, resolved::ResolvedSymbol};
 #[cfg(test)]
-use tokio_stream::wrappers::ReceiverStream;
-
-#[cfg(test)]
-use crate::models::resolved::ResolvedSymbol;
-
-use crate::models::{self};
+use crate::resolver::CancellableQuery;
+use crate::resolver::{Error, Namespace};
 
 /// The Resolver trait defines the core functionality required for resolving
 /// semantic symbols from indexed source code, within registered workspaces.
 #[cfg_attr(
     test,
-    automock(type QueryContext=super::types::Context; type QueryResult=ReceiverStream<ResolvedSymbol>;)
+    automock(type QueryContext=super::types::Context; type QueryResult=CancellableQuery;)
 )]
 pub trait Resolver: Send + Sync + Debug {
     #[allow(missing_docs)]
@@ -30,6 +27,53 @@ pub trait Resolver: Send + Sync + Debug {
     /// task which resolves symbols from the index just-in-time. But in practice,
     /// the implementation details are left up to the resolver.
     fn query(&self, query: String, ctx: Self::QueryContext) -> Self::QueryResult;
+
+    /// Find every occurrence of a symbol, scoped to a single language, so that editor
+    /// integrations can implement "find all references" (and rename previews) on top of
+    /// the [`models::parsed::Occurrence`] roles recorded at index time.
+    ///
+    /// Matching is workspace-local and by symbol name, since names are only meaningfully
+    /// comparable within the same language. `filter` controls whether definitions,
+    /// references, or both are returned; the occurrences come back in their natural
+    /// derived order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying index could not be queried.
+    fn find_references(
+        &self,
+        symbol_name: &str,
+        language: models::parsed::Language,
+        filter: ReferenceFilter,
+    ) -> impl Future<Output = std::result::Result<Vec<models::parsed::Occurrence>, Error>> + Send;
+
+    /// Record that `symbol` was selected (e.g. navigated to) by a caller, so that future
+    /// queries can rank it more highly based on how frequently and recently it's been used.
+    ///
+    /// See [`crate::resolver::frecency`] for how this feeds into scoring.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying index could not be updated.
+    fn record_access(
+        &self,
+        symbol: &ResolvedSymbol,
+    ) -> impl Future<Output = std::result::Result<(), Error>> + Send;
+}
+
+/// Controls which roles a [`Resolver::find_references`] query returns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceFilter {
+    /// Only return occurrences with a [`models::parsed::SymbolRole::Definition`] role.
+    DefinitionsOnly,
+
+    /// Only return occurrences which aren't a [`models::parsed::SymbolRole::Definition`], i.e.
+    /// references. This is the default, since it matches "find references" semantics.
+    #[default]
+    ReferencesOnly,
+
+    /// Return every occurrence, both definitions and references.
+    All,
 }
 
 /// The context in which a query was executed from.
@@ -48,6 +92,31 @@ pub struct Context {
     ///
     /// Queries where the context provides [`Option::None`] or an empty [`Vec`] will return symbols of all kinds.
     pub symbol_kinds: Option<Vec<models::parsed::SymbolKind>>,
+
+    /// The namespace the query was issued from, if known (e.g. a type position vs. a value
+    /// position).
+    ///
+    /// When set, symbols whose kind occupies the same [`Namespace`] (see [`Namespace::of`])
+    /// receive a score bonus, and symbols in a different namespace receive a penalty. Symbols
+    /// whose kind doesn't map to a namespace are unaffected either way.
+    pub namespace: Option<Namespace>,
+
+    /// Caller-configurable overrides for the test-harness/entrypoint detection heuristics used
+    /// while scoring, so a project's own layout and language conventions can be taken into
+    /// account (e.g. a monorepo keeping integration tests under `spec/`, or using `*_it.go`).
+    pub heuristics: ScoringHeuristics,
+
+    /// Caller-configurable overrides (or additions) to the built-in per-symbol-kind score
+    /// weight table consulted while scoring, so a project whose relevant declarations aren't
+    /// well represented by Onoma's defaults (e.g. `Lemma`/`Theorem` in a Lean codebase) can be
+    /// ranked appropriately.
+    pub kind_weights: ScoringConfig,
+
+    /// Whether the query should close once the index has been scanned once, or stay open and
+    /// keep streaming matches as the index changes.
+    ///
+    /// See [`StreamMode`].
+    pub stream_mode: StreamMode,
 }
 
 impl Context {
@@ -66,4 +135,136 @@ impl Context {
 
         self
     }
+
+    /// Set the namespace the query was issued from.
+    #[must_use]
+    pub fn with_namespace(mut self, namespace: Namespace) -> Self {
+        self.namespace = Some(namespace);
+
+        self
+    }
+
+    /// Set the test-harness/entrypoint scoring heuristics.
+    #[must_use]
+    pub fn with_heuristics(mut self, heuristics: ScoringHeuristics) -> Self {
+        self.heuristics = heuristics;
+
+        self
+    }
+
+    /// Set overrides (or additions) to the per-symbol-kind score weight table.
+    #[must_use]
+    pub fn with_kind_weights(mut self, kind_weights: ScoringConfig) -> Self {
+        self.kind_weights = kind_weights;
+
+        self
+    }
+
+    /// Set whether the query should close after its initial scan, or stay open and keep
+    /// streaming matches live.
+    #[must_use]
+    pub fn with_stream_mode(mut self, stream_mode: StreamMode) -> Self {
+        self.stream_mode = stream_mode;
+
+        self
+    }
+}
+
+/// Controls whether a [`Resolver::query`] closes once the index has been scanned, or stays
+/// open and keeps streaming matches as the index changes underneath it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StreamMode {
+    /// The stream closes once every matching symbol from the initial scan has been sent.
+    ///
+    /// This is a one-shot snapshot of the index at the time the query was issued.
+    #[default]
+    Snapshot,
+
+    /// After the initial scan drains, the stream stays open and keeps pushing batches of
+    /// newly matching symbols as the underlying index changes (e.g. a
+    /// [`crate::watcher::Watcher`] re-indexing a saved file), until the caller drops the
+    /// returned [`crate::resolver::CancellableQuery`].
+    Subscribe,
+}
+
+/// Caller-configurable overrides for the heuristics used to detect test-harness and
+/// entrypoint files while scoring, and the score adjustments applied when they match.
+///
+/// The pattern and filename lists are additive on top of Onoma's built-in defaults (see
+/// [`crate::resolver::utils::is_entrypoint_file`] and
+/// [`crate::resolver::utils::is_part_of_test_harness`]), while the penalty fields replace the
+/// built-in penalties when set, so a caller only needs to set what their project actually needs.
+#[derive(Debug, Default, Clone)]
+pub struct ScoringHeuristics {
+    /// Extra filename suffixes (beyond the built-in defaults) that mark a file as
+    /// part of a test harness.
+    pub test_file_patterns: Vec<String>,
+
+    /// Extra exact filenames (beyond the built-in defaults) that mark a file as an
+    /// entrypoint/re-export file.
+    pub entrypoint_filenames: Vec<String>,
+
+    /// Overrides the default score penalty applied to symbols in a test-harness file, when set.
+    pub test_harness_penalty: Option<i64>,
+
+    /// Overrides the default score penalty applied to symbols in an entrypoint file, when set.
+    pub entrypoint_penalty: Option<i64>,
+}
+
+impl ScoringHeuristics {
+    /// Add extra test-file patterns to match, on top of the built-in defaults.
+    #[must_use]
+    pub fn with_test_file_patterns(mut self, patterns: &[String]) -> Self {
+        self.test_file_patterns = patterns.to_vec();
+
+        self
+    }
+
+    /// Add extra entrypoint filenames to match, on top of the built-in defaults.
+    #[must_use]
+    pub fn with_entrypoint_filenames(mut self, filenames: &[String]) -> Self {
+        self.entrypoint_filenames = filenames.to_vec();
+
+        self
+    }
+
+    /// Override the score penalty applied to symbols in a test-harness file.
+    #[must_use]
+    pub fn with_test_harness_penalty(mut self, penalty: i64) -> Self {
+        self.test_harness_penalty = Some(penalty);
+
+        self
+    }
+
+    /// Override the score penalty applied to symbols in an entrypoint file.
+    #[must_use]
+    pub fn with_entrypoint_penalty(mut self, penalty: i64) -> Self {
+        self.entrypoint_penalty = Some(penalty);
+
+        self
+    }
+}
+
+/// Caller-configurable overrides (or additions) to the built-in per-[`models::parsed::SymbolKind`]
+/// score weight table consulted while scoring (see
+/// [`crate::resolver::weight::default_kind_weight`]).
+///
+/// A kind present here always takes precedence over the built-in default, so a caller only
+/// needs to set what their project actually needs, e.g. boosting `Lemma`/`Theorem` when indexing
+/// a Lean codebase, where those are the top-level declarations users actually search for.
+#[derive(Debug, Default, Clone)]
+pub struct ScoringConfig {
+    /// A signed score delta per symbol kind, layered over
+    /// [`crate::resolver::weight::default_kind_weight`].
+    pub kind_weights: std::collections::HashMap<models::parsed::SymbolKind, i64>,
+}
+
+impl ScoringConfig {
+    /// Override (or add) the score delta applied to a particular symbol kind.
+    #[must_use]
+    pub fn with_kind_weight(mut self, kind: models::parsed::SymbolKind, weight: i64) -> Self {
+        self.kind_weights.insert(kind, weight);
+
+        self
+    }
 }
@@ -6,8 +6,11 @@ use std::{ffi::OsStr, path::Path};
 /// Though not a guarantee, these files are _generally_ used to re-export
 /// interfaces or act as an entrypoint into other files with business logic,
 /// rather than housing the business logic themselves.
-pub fn is_entrypoint_file(filename: &str) -> bool {
-    matches!(
+///
+/// `extra_filenames` lets a caller extend the built-in defaults with project-specific
+/// entrypoint filenames (see [`crate::resolver::ScoringHeuristics::entrypoint_filenames`]).
+pub fn is_entrypoint_file(filename: &str, extra_filenames: &[String]) -> bool {
+    let is_default_entrypoint = matches!(
         filename,
         "mod.rs"
             | "lib.rs"
@@ -26,7 +29,9 @@ pub fn is_entrypoint_file(filename: &str) -> bool {
             | "index.php"
             | "main.rb"
             | "index.rb"
-    )
+    );
+
+    is_default_entrypoint || extra_filenames.iter().any(|name| name == filename)
 }
 
 /// Check if a given file (i.e. `path/to/some/file/file.test.ts`) is in what would
@@ -35,7 +40,11 @@ pub fn is_entrypoint_file(filename: &str) -> bool {
 ///
 /// Though not a guarantee (also, not all cases are testable in this mechanism), these files are
 /// _generally_ used for testing, and as such don't house business logic of their own.
-pub fn is_part_of_test_harness(path: &Path) -> bool {
+///
+/// `extra_patterns` lets a caller extend the built-in suffix defaults with
+/// project-specific conventions (see [`crate::resolver::ScoringHeuristics::test_file_patterns`]),
+/// e.g. a monorepo using `*_it.go` for integration tests.
+pub fn is_part_of_test_harness(path: &Path, extra_patterns: &[String]) -> bool {
     // Common test file patterns
     let test_file_patterns = [
         // JavaScript / TypeScript
@@ -77,9 +86,12 @@ pub fn is_part_of_test_harness(path: &Path) -> bool {
 
     // Check if the filename or directory matches test heuristics
     if let Some(filename) = path.file_name().and_then(OsStr::to_str)
-        && test_file_patterns
+        && (test_file_patterns
             .iter()
             .any(|pattern| filename.ends_with(pattern))
+            || extra_patterns
+                .iter()
+                .any(|pattern| filename.ends_with(pattern.as_str())))
     {
         return true;
     }
@@ -180,7 +192,7 @@ mod tests {
     pub fn test_in_tests_folder() {
         let file = PathBuf::from_iter(["some", "root", "tests", "SomeFileTest.php"]);
 
-        let is_in_test_harness = super::is_part_of_test_harness(file.as_path());
+        let is_in_test_harness = super::is_part_of_test_harness(file.as_path(), &[]);
 
         assert!(is_in_test_harness);
     }
@@ -189,7 +201,7 @@ mod tests {
     pub fn test_in_test_harness_file_js() {
         let file = PathBuf::from_iter(["some", "root", "some_file.test.js"]);
 
-        let is_in_test_harness = super::is_part_of_test_harness(file.as_path());
+        let is_in_test_harness = super::is_part_of_test_harness(file.as_path(), &[]);
 
         assert!(is_in_test_harness);
     }
@@ -198,8 +210,30 @@ mod tests {
     pub fn test_not_in_test_harness() {
         let file = PathBuf::from_iter(["some", "root", "just_a_normal_file.py"]);
 
-        let is_in_test_harness = super::is_part_of_test_harness(file.as_path());
+        let is_in_test_harness = super::is_part_of_test_harness(file.as_path(), &[]);
 
         assert!(!is_in_test_harness);
     }
+
+    #[test]
+    pub fn test_in_test_harness_with_extra_pattern() {
+        let file = PathBuf::from_iter(["some", "root", "widget_it.go"]);
+
+        assert!(!super::is_part_of_test_harness(file.as_path(), &[]));
+
+        assert!(super::is_part_of_test_harness(
+            file.as_path(),
+            &["_it.go".to_string()]
+        ));
+    }
+
+    #[test]
+    pub fn test_entrypoint_file_with_extra_filename() {
+        assert!(!super::is_entrypoint_file("app.go", &[]));
+
+        assert!(super::is_entrypoint_file(
+            "app.go",
+            &["app.go".to_string()]
+        ));
+    }
 }
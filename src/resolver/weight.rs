@@ -1,4 +1,7 @@
-use crate::resolver::constant::{self, DEFAULT_SCORE};
+use crate::{
+    models::parsed::SymbolKind,
+    resolver::constant::{self, DEFAULT_SCORE},
+};
 
 /// 3.5% bonus for common symbol kinds.
 pub const COMMON_SYMBOL_KINDS_SCORE_BONUS: i64 = (constant::DEFAULT_SCORE * 35) / 1000;
@@ -9,6 +12,10 @@ pub const INFREQUENT_SYMBOL_KINDS_SCORE_BONUS: i64 = (constant::DEFAULT_SCORE *
 /// -1.5% penalty for uncommon symbol kinds.
 pub const UNCOMMON_SYMBOL_KINDS_SCORE_PENALTY: i64 = -((constant::DEFAULT_SCORE * 15) / 1000);
 
+/// -3% penalty for locals and sub-symbols (e.g. parameters, type parameters), which are rarely
+/// what a user searching for a symbol by name actually wants.
+pub const LOCAL_SYMBOL_KINDS_SCORE_PENALTY: i64 = -((constant::DEFAULT_SCORE * 30) / 1000);
+
 /// 0.5% penalty for symbols which are part of a test harness (i.e. it's likely a test
 /// case, part of a test file, etc.).
 pub const TEST_HARNESS_SCORE_PENALTY: i64 = -((constant::DEFAULT_SCORE * 5) / 1000);
@@ -17,6 +24,30 @@ pub const TEST_HARNESS_SCORE_PENALTY: i64 = -((constant::DEFAULT_SCORE * 5) / 10
 /// filter out re-exports.
 pub const ENTRYPOINT_FILE_SCORE_PENALTY: i64 = -(constant::DEFAULT_SCORE / 100);
 
+/// 2% bonus when a symbol's namespace matches the namespace the query was issued from.
+pub const NAMESPACE_MATCH_SCORE_BONUS: i64 = (constant::DEFAULT_SCORE * 20) / 1000;
+
+/// 2% penalty when a symbol's namespace doesn't match the namespace the query was issued from.
+pub const NAMESPACE_MISMATCH_SCORE_PENALTY: i64 = -((constant::DEFAULT_SCORE * 20) / 1000);
+
+/// 1% bonus per unit of frecency multiplier (see
+/// [`crate::resolver::frecency::FrecencyEntry::multiplier`]), so a symbol which has
+/// repeatedly and recently been navigated to can easily out-rank the other heuristics.
+pub const FRECENCY_SCORE_BONUS_PER_UNIT: i64 = constant::DEFAULT_SCORE / 100;
+
+/// Convert a symbol's frecency multiplier into a saturating score bonus, in the same
+/// per-mille units as every other weight in this module.
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+pub fn calculate_frecency_score_bonus(multiplier: f64) -> i64 {
+    let scaled = (FRECENCY_SCORE_BONUS_PER_UNIT as f64) * multiplier;
+
+    if scaled >= i64::MAX as f64 {
+        i64::MAX
+    } else {
+        scaled as i64
+    }
+}
+
 /// 1% penalty for each directory distance from the current focused file (up to max of
 /// 8 directories - aka a 8% penalty)
 pub fn calculate_distance_score_penalty(distance: usize) -> i64 {
@@ -54,10 +85,53 @@ pub fn calculate_fuzzy_match_bonus(fuzzy_match: &frizbee::Match) -> i64 {
     }
 }
 
+/// The built-in, per-[`SymbolKind`] score delta consulted by
+/// [`crate::resolver::scoring::calculate_score`], unless overridden via a
+/// [`crate::resolver::ScoringConfig`].
+///
+/// Borrows rust-analyzer's completion-relevance approach: top-level declarations a user is
+/// actually likely to be searching for by name (functions, types, modules, ...) get a bonus,
+/// while locals and sub-symbols (parameters, type parameters, ...) get a penalty, so a
+/// `Parameter` match doesn't out-rank a `Function` match purely on fuzzy match strength.
+#[must_use]
+pub const fn default_kind_weight(kind: SymbolKind) -> i64 {
+    match kind {
+        SymbolKind::Function
+        | SymbolKind::Method
+        | SymbolKind::Struct
+        | SymbolKind::Type
+        | SymbolKind::TypeAlias
+        | SymbolKind::Class
+        | SymbolKind::Trait
+        | SymbolKind::Module
+        | SymbolKind::Constant
+        | SymbolKind::Enum
+        | SymbolKind::EnumMember
+        | SymbolKind::Interface => COMMON_SYMBOL_KINDS_SCORE_BONUS,
+
+        SymbolKind::Variable | SymbolKind::Field | SymbolKind::Property => {
+            INFREQUENT_SYMBOL_KINDS_SCORE_BONUS
+        }
+
+        SymbolKind::Package | SymbolKind::SelfParameter => UNCOMMON_SYMBOL_KINDS_SCORE_PENALTY,
+
+        SymbolKind::Parameter
+        | SymbolKind::TypeParameter
+        | SymbolKind::Key
+        | SymbolKind::Null
+        | SymbolKind::Operator
+        | SymbolKind::Unknown => LOCAL_SYMBOL_KINDS_SCORE_PENALTY,
+
+        _ => 0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
 
+    use crate::models::parsed::SymbolKind;
+
     #[rstest]
     #[case(0, 0)]
     #[case(1, -1)]
@@ -76,4 +150,25 @@ mod tests {
             super::calculate_distance_score_penalty(distance)
         );
     }
+
+    #[rstest]
+    #[case(0.0, 0)]
+    #[case(1.0, 10)]
+    #[case(4.0, 40)]
+    #[case(400.0, 4000)]
+    pub fn test_frecency_weighting(#[case] multiplier: f64, #[case] expected_bonus: i64) {
+        assert_eq!(expected_bonus, super::calculate_frecency_score_bonus(multiplier));
+    }
+
+    #[rstest]
+    #[case(SymbolKind::Function, super::COMMON_SYMBOL_KINDS_SCORE_BONUS)]
+    #[case(SymbolKind::Module, super::COMMON_SYMBOL_KINDS_SCORE_BONUS)]
+    #[case(SymbolKind::Variable, super::INFREQUENT_SYMBOL_KINDS_SCORE_BONUS)]
+    #[case(SymbolKind::Package, super::UNCOMMON_SYMBOL_KINDS_SCORE_PENALTY)]
+    #[case(SymbolKind::Parameter, super::LOCAL_SYMBOL_KINDS_SCORE_PENALTY)]
+    #[case(SymbolKind::Unknown, super::LOCAL_SYMBOL_KINDS_SCORE_PENALTY)]
+    #[case(SymbolKind::File, 0)]
+    pub fn test_default_kind_weight(#[case] kind: SymbolKind, #[case] expected_weight: i64) {
+        assert_eq!(expected_weight, super::default_kind_weight(kind));
+    }
 }
@@ -0,0 +1,176 @@
+use std::str::FromStr;
+
+use crate::{models, scip};
+
+/// Flatten a one-based Onoma [`models::parsed::Range`] into SCIP's zero-based
+/// `[start_line, start_column, end_line, end_column]` array.
+#[must_use]
+pub fn range_to_scip(range: &models::parsed::Range) -> Vec<i32> {
+    [
+        range.start_line,
+        range.start_column,
+        range.end_line,
+        range.end_column,
+    ]
+    .into_iter()
+    .map(|component| i32::try_from(component.saturating_sub(1)).unwrap_or(i32::MAX))
+    .collect()
+}
+
+/// Expand a SCIP zero-based `[start_line, start_column, end_line, end_column]` array into
+/// a one-based Onoma [`models::parsed::Range`].
+///
+/// Returns `None` if `range` doesn't have exactly four elements, or any of them are
+/// negative.
+#[must_use]
+pub fn range_from_scip(range: &[i32]) -> Option<models::parsed::Range> {
+    let [start_line, start_column, end_line, end_column] = *range else {
+        return None;
+    };
+
+    Some(models::parsed::Range::new(
+        usize::try_from(start_line).ok()?.saturating_add(1),
+        usize::try_from(end_line).ok()?.saturating_add(1),
+        usize::try_from(start_column).ok()?.saturating_add(1),
+        usize::try_from(end_column).ok()?.saturating_add(1),
+    ))
+}
+
+/// Encode Onoma [`models::parsed::Roles`] into the SCIP symbol-role bitmask.
+#[must_use]
+pub fn roles_to_scip(roles: &models::parsed::Roles) -> i32 {
+    roles.iter().fold(0, |bitmask, role| {
+        if *role == models::parsed::SymbolRole::Definition {
+            bitmask | scip::SymbolRole::Definition as i32
+        } else {
+            bitmask
+        }
+    })
+}
+
+/// Decode a SCIP symbol-role bitmask into Onoma [`models::parsed::Roles`].
+///
+/// Any bit Onoma doesn't yet have a first-class [`models::parsed::SymbolRole`] for is
+/// preserved as [`models::parsed::SymbolRole::Other`].
+#[must_use]
+pub fn roles_from_scip(bitmask: i32) -> models::parsed::Roles {
+    let mut roles = Vec::new();
+
+    if bitmask & (scip::SymbolRole::Definition as i32) != 0 {
+        roles.push(models::parsed::SymbolRole::Definition);
+    }
+
+    if bitmask & (scip::SymbolRole::Import as i32) != 0 {
+        roles.push(models::parsed::SymbolRole::Other("Import".to_string()));
+    }
+
+    if bitmask & (scip::SymbolRole::WriteAccess as i32) != 0 {
+        roles.push(models::parsed::SymbolRole::Other(
+            "WriteAccess".to_string(),
+        ));
+    }
+
+    if bitmask & (scip::SymbolRole::ReadAccess as i32) != 0 {
+        roles.push(models::parsed::SymbolRole::Other("ReadAccess".to_string()));
+    }
+
+    models::parsed::Roles(roles)
+}
+
+/// Encode a [`models::parsed::SymbolKind`] as a SCIP [`scip::Kind`].
+///
+/// `SymbolKind` was itself modelled on SCIP's `Kind` enum, so every variant's name matches
+/// 1:1 except [`models::parsed::SymbolKind::Unknown`], which corresponds to
+/// [`scip::Kind::UnspecifiedKind`]. Any variant Onoma has added since that this schema doesn't
+/// yet model falls back to [`scip::Kind::UnspecifiedKind`] rather than failing the export.
+#[must_use]
+pub fn kind_to_scip(kind: models::parsed::SymbolKind) -> scip::Kind {
+    if kind == models::parsed::SymbolKind::Unknown {
+        return scip::Kind::UnspecifiedKind;
+    }
+
+    scip::Kind::from_str_name(&kind.to_string()).unwrap_or(scip::Kind::UnspecifiedKind)
+}
+
+/// Decode a SCIP [`scip::Kind`] into a [`models::parsed::SymbolKind`].
+///
+/// The inverse of [`kind_to_scip`]; [`scip::Kind::UnspecifiedKind`] and any name Onoma
+/// doesn't recognise both decode to [`models::parsed::SymbolKind::Unknown`].
+#[must_use]
+pub fn kind_from_scip(kind: scip::Kind) -> models::parsed::SymbolKind {
+    if kind == scip::Kind::UnspecifiedKind {
+        return models::parsed::SymbolKind::Unknown;
+    }
+
+    models::parsed::SymbolKind::from_str(kind.as_str_name())
+        .unwrap_or(models::parsed::SymbolKind::Unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        kind_from_scip, kind_to_scip, range_from_scip, range_to_scip, roles_from_scip,
+        roles_to_scip,
+    };
+    use crate::{models, scip};
+
+    #[test]
+    fn test_range_round_trips_through_scip() {
+        let range = models::parsed::Range::new(2, 4, 1, 9);
+
+        let scip_range = range_to_scip(&range);
+        assert_eq!(scip_range, vec![1, 0, 3, 8]);
+
+        assert_eq!(range_from_scip(&scip_range), Some(range));
+    }
+
+    #[test]
+    fn test_range_from_scip_rejects_malformed_arrays() {
+        assert_eq!(range_from_scip(&[1, 2, 3]), None);
+    }
+
+    #[test]
+    fn test_roles_round_trip_definition() {
+        let roles = models::parsed::Roles(vec![models::parsed::SymbolRole::Definition]);
+
+        let bitmask = roles_to_scip(&roles);
+        assert_eq!(bitmask, scip::SymbolRole::Definition as i32);
+
+        assert_eq!(roles_from_scip(bitmask), roles);
+    }
+
+    #[test]
+    fn test_roles_from_scip_preserves_unmodelled_bits() {
+        let roles = roles_from_scip(scip::SymbolRole::Import as i32);
+
+        assert_eq!(
+            roles,
+            models::parsed::Roles(vec![models::parsed::SymbolRole::Other(
+                "Import".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn test_kind_round_trips_through_scip() {
+        let kind = models::parsed::SymbolKind::Struct;
+
+        let scip_kind = kind_to_scip(kind);
+        assert_eq!(scip_kind, scip::Kind::Struct);
+
+        assert_eq!(kind_from_scip(scip_kind), kind);
+    }
+
+    #[test]
+    fn test_kind_unknown_round_trips_to_unspecified() {
+        assert_eq!(
+            kind_to_scip(models::parsed::SymbolKind::Unknown),
+            scip::Kind::UnspecifiedKind
+        );
+
+        assert_eq!(
+            kind_from_scip(scip::Kind::UnspecifiedKind),
+            models::parsed::SymbolKind::Unknown
+        );
+    }
+}
@@ -0,0 +1,3 @@
+// Generated from `proto/scip.proto` by `prost-build` in `build.rs`. Not hand-written, so
+// lint and doc requirements which apply to the rest of the crate are relaxed here.
+include!(concat!(env!("OUT_DIR"), "/scip.rs"));
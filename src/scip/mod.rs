@@ -0,0 +1,21 @@
+//! A bridge to and from the SCIP (SCIP Code Intelligence Protocol) protobuf format.
+//!
+//! This lets Onoma emit its own index as a standard SCIP file, and ingest SCIP indexes
+//! produced by other tools (e.g. `scip-typescript`, `scip-clang`) into a
+//! [`crate::indexer::DatabaseBackedIndexer`] database, so that symbols can be resolved for
+//! languages Onoma doesn't parse itself.
+//!
+//! The protobuf schema lives at `proto/scip.proto`, and is compiled into the types
+//! re-exported here by `build.rs`, using `prost-build`.
+
+mod convert;
+
+#[allow(missing_docs, clippy::all, clippy::pedantic, clippy::nursery)]
+mod generated;
+
+pub use convert::{
+    kind_from_scip, kind_to_scip, range_from_scip, range_to_scip, roles_from_scip, roles_to_scip,
+};
+pub use generated::{
+    Document, Index, Kind, Metadata, Occurrence, SymbolInformation, SymbolRole, ToolInfo,
+};
@@ -8,9 +8,12 @@ use notify_debouncer_mini::{DebouncedEvent, Debouncer, new_debouncer_opt};
 
 mod constant;
 mod error;
+mod queue;
 mod types;
 
 pub use error::Error;
+pub use queue::{QueueEvent, QueueSubscription};
+use queue::IndexQueue;
 use tokio::{
     sync::{
         Mutex,
@@ -41,6 +44,10 @@ where
     debouncer: Arc<Mutex<Option<Debouncer<RecommendedWatcher>>>>,
     handle: Arc<Mutex<Option<JoinHandle<()>>>>,
     indexer: Arc<Mutex<I>>,
+
+    /// The background indexing queue fed by [`Watcher::on_event`] once [`Watcher::start`] has
+    /// been called - see [`IndexQueue`].
+    queue: Arc<Mutex<Option<IndexQueue<I>>>>,
 }
 
 impl<I> Watcher<I>
@@ -57,6 +64,7 @@ where
             debouncer: Arc::default(),
             handle: Arc::default(),
             indexer: Arc::new(Mutex::new(indexer)),
+            queue: Arc::default(),
         }
     }
 
@@ -65,6 +73,11 @@ where
     /// This generally precedes a call to [`Watcher::start`], which will incrementally
     /// update the index when files change.
     ///
+    /// Progress is checkpointed per-workspace as the index proceeds (see
+    /// [`crate::indexer::Indexer::index_workspaces_with_job`]), so a process interrupted
+    /// partway through a large workspace doesn't have to re-walk everything from scratch next
+    /// time - see [`Watcher::resume_full_index`].
+    ///
     /// # Errors
     ///
     /// Returns a list of errors for each workspace which could not be successfully indexed.
@@ -72,7 +85,75 @@ where
         self.indexer
             .lock()
             .await
-            .index_workspaces()
+            .index_workspaces_with_job()
+            .await
+            .map_err(|errors| {
+                errors
+                    .into_iter()
+                    .map(watcher::Error::IndexingFailed)
+                    .collect::<Vec<_>>()
+            })?;
+
+        Ok(())
+    }
+
+    /// Run a full index on all files in the indexer's workspaces, using a bounded pool of
+    /// worker tasks per workspace to parse files concurrently instead of one at a time (see
+    /// [`crate::indexer::Indexer::index_workspaces_parallel`]).
+    ///
+    /// `max_concurrency` caps how many files are parsed at once; pass `None` to default to
+    /// [`std::thread::available_parallelism`]. Unlike [`Watcher::run_full_index`], progress is
+    /// not checkpointed, so an interrupted run has to be repeated from scratch - prefer this
+    /// over [`Watcher::run_full_index`] for a cold, first-time index of a large workspace,
+    /// where cutting wall-clock time matters more than resumability.
+    ///
+    /// # Errors
+    ///
+    /// Returns a list of errors for each file which could not be successfully indexed.
+    pub async fn run_full_index_parallel(
+        &self,
+        max_concurrency: Option<usize>,
+    ) -> std::result::Result<(), Vec<watcher::Error>> {
+        self.indexer
+            .lock()
+            .await
+            .index_workspaces_parallel(max_concurrency)
+            .await
+            .map_err(|errors| {
+                errors
+                    .into_iter()
+                    .map(watcher::Error::IndexingFailed)
+                    .collect::<Vec<_>>()
+            })?;
+
+        Ok(())
+    }
+
+    /// Resume a full index left incomplete by a previous run (e.g. because the process
+    /// crashed, or was killed, partway through).
+    ///
+    /// Unlike [`Watcher::run_full_index`], which always starts a brand new job (and walk) for
+    /// every workspace, this first looks for a job left in
+    /// [`crate::indexer::JobStatus::Running`] against each workspace and, if one exists,
+    /// continues it from its persisted cursor (see
+    /// [`crate::indexer::Indexer::resume_workspaces_with_job`]) instead of re-walking and
+    /// re-indexing files it already completed. A workspace with nothing outstanding just gets
+    /// a fresh job, same as [`Watcher::run_full_index`].
+    ///
+    /// Note that the indexer itself already replays any job left `Running` the moment it's
+    /// constructed (see [`crate::indexer::DatabaseBackedIndexer::new`]), so by the time a
+    /// caller reaches this method that replay may already be done - this exists as the
+    /// explicitly-named entry point for callers who specifically mean "pick up where indexing
+    /// left off" rather than "index from scratch", so that intent is visible at the call site.
+    ///
+    /// # Errors
+    ///
+    /// Returns a list of errors for each workspace which could not be successfully indexed.
+    pub async fn resume_full_index(&self) -> std::result::Result<(), Vec<watcher::Error>> {
+        self.indexer
+            .lock()
+            .await
+            .resume_workspaces_with_job()
             .await
             .map_err(|errors| {
                 errors
@@ -98,7 +179,10 @@ where
 
         log::debug!("Watching: {:?}", self.indexer.lock().await.get_workspaces());
 
-        let indexer = Arc::clone(&self.indexer);
+        let queue = IndexQueue::new(Arc::clone(&self.indexer));
+        *self.queue.lock().await = Some(queue);
+
+        let queue = Arc::clone(&self.queue);
 
         let handle = tokio::spawn(async move {
             while let Some(res) = rx.recv().await {
@@ -113,10 +197,8 @@ where
                                 .collect::<Vec<&Path>>()
                         );
 
-                        if let Err(e) =
-                            Self::on_event(Arc::clone(&indexer), events.into_iter()).await
-                        {
-                            log::error!("Indexing error: {e:?}");
+                        if let Some(queue) = queue.lock().await.as_ref() {
+                            Self::on_event(queue, events.into_iter()).await;
                         }
                     }
                     Err(e) => log::error!("Watch error: {e:?}"),
@@ -135,54 +217,65 @@ where
     pub async fn stop(&self) {
         let debouncer = self.debouncer.lock().await.take();
         let handle = self.handle.lock().await.take();
+        let queue = self.queue.lock().await.take();
 
-        // They'll both be dropped and safely shut down when they go
+        // They'll all be dropped and safely shut down when they go
         // out of scope, but just for verbosity, drop them explicitly
         drop(handle);
         drop(debouncer);
+        drop(queue);
 
         log::debug!("Watcher stopped");
     }
 
-    /// Process any events received from the debouncer, by triggering the indexer for
-    /// all files.
+    /// The number of paths still waiting to be drained from the background indexing queue, plus
+    /// the one (if any) currently being processed.
     ///
-    /// It is the responsibility of the Indexer to ensure the file is relevant for its
-    /// index (i.e. it's a supported programming language, etc.).
+    /// Returns `0` if the watcher has not been [`Watcher::start`]ed, since there is no queue to
+    /// report on yet.
+    pub async fn pending_count(&self) -> usize {
+        match self.queue.lock().await.as_ref() {
+            Some(queue) => queue.pending_count().await,
+            None => 0,
+        }
+    }
+
+    /// Whether a given path is currently queued, or actively being (de-)indexed, by the
+    /// background indexing queue.
+    ///
+    /// A caller (e.g. a [`crate::resolver::Resolver`]) can poll this before querying, to avoid
+    /// racing a change that's still working its way through the queue. Returns `false` if the
+    /// watcher has not been [`Watcher::start`]ed.
+    pub async fn is_indexing(&self, path: &Path) -> bool {
+        match self.queue.lock().await.as_ref() {
+            Some(queue) => queue.is_indexing(path).await,
+            None => false,
+        }
+    }
+
+    /// Subscribe to real-time [`QueueEvent`]s as paths move through the background indexing
+    /// queue.
+    ///
+    /// Returns `None` if the watcher has not been [`Watcher::start`]ed, since there is no queue
+    /// to subscribe to yet.
+    pub async fn subscribe(&self) -> Option<QueueSubscription> {
+        self.queue.lock().await.as_ref().map(IndexQueue::subscribe)
+    }
+
+    /// Enqueue any paths reported by the debouncer onto the background indexing queue (see
+    /// [`IndexQueue`]), rather than indexing them inline.
+    ///
+    /// Deciding whether a path should be indexed or de-indexed is deferred to the queue's
+    /// worker, since by the time it's drained the file may have changed again.
     async fn on_event(
-        indexer: Arc<Mutex<I>>,
+        queue: &IndexQueue<I>,
         events: impl IntoIterator<Item = DebouncedEvent> + Send,
-    ) -> Result<()> {
+    ) {
         for path in events.into_iter().map(|event| event.path).dedup() {
-            match path {
-                path if path.exists() && path.is_file() => {
-                    log::debug!("Indexing file change: {}", path.display());
-
-                    indexer
-                        .lock()
-                        .await
-                        .index(&path)
-                        .await
-                        .map_err(watcher::Error::IndexingFailed)?;
-                }
-                path if !path.exists() => {
-                    log::debug!(
-                        "Deindexing as the file no longer exists: {}",
-                        path.display()
-                    );
-
-                    indexer
-                        .lock()
-                        .await
-                        .deindex(&path)
-                        .await
-                        .map_err(watcher::Error::IndexingFailed)?;
-                }
-                _ => {}
-            }
-        }
+            log::debug!("Queueing file change: {}", path.display());
 
-        Ok(())
+            queue.enqueue(path).await;
+        }
     }
 
     /// Setup a debouncer, and configure a channel to receive the debounced events in real-time from the
@@ -0,0 +1,288 @@
+//! A coalescing background indexing queue, decoupled from the filesystem debouncer callback
+//! that feeds it.
+
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tokio::{
+    sync::{Mutex, Notify, broadcast},
+    task::JoinHandle,
+};
+use tokio_stream::{
+    Stream,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
+
+use crate::{indexer::Indexer, watcher};
+
+/// How many not-yet-received [`QueueEvent`]s a lagging [`QueueSubscription`] can buffer before
+/// older ones are dropped.
+const QUEUE_EVENT_CAPACITY: usize = 1024;
+
+/// A progress event published as a path moves through an [`IndexQueue`].
+#[derive(Debug, Clone)]
+pub enum QueueEvent {
+    /// A path was enqueued (or re-enqueued, coalescing with a change already waiting to be
+    /// drained) for indexing.
+    Queued(PathBuf),
+
+    /// A path was successfully (re-)indexed.
+    Indexed(PathBuf),
+
+    /// A path was successfully de-indexed, because it no longer exists on disk.
+    Deindexed(PathBuf),
+
+    /// A path could not be indexed or de-indexed.
+    Failed {
+        /// The path which failed.
+        path: PathBuf,
+        /// The underlying error, shared rather than owned since [`QueueEvent`] has to be
+        /// [`Clone`] to be broadcast to every [`QueueSubscription`].
+        error: Arc<watcher::Error>,
+    },
+}
+
+/// What to do with a path popped off an [`IndexQueue`], decided at drain time rather than
+/// enqueue time, since the file's existence may have changed in between.
+enum Operation {
+    Index,
+    Deindex,
+}
+
+/// A coalescing background indexing queue, fed by [`IndexQueue::enqueue`] and drained by a
+/// single worker task independent of whatever's calling `enqueue` (e.g. a debouncer callback).
+///
+/// Paths are deduplicated by their canonicalized form (falling back to the raw path if it no
+/// longer exists, e.g. after a delete) as they're enqueued, so rapid repeated saves of the same
+/// file collapse into a single re-index rather than one per save. The worker continues past
+/// individual file failures - each is reported as a [`QueueEvent::Failed`] via
+/// [`IndexQueue::subscribe`] rather than aborting the rest of the queue.
+#[derive(Debug)]
+pub(crate) struct IndexQueue<I>
+where
+    I: Indexer + Send + Sync + 'static,
+{
+    /// Canonical path -> original (as reported by the caller) path, for every path waiting to
+    /// be drained. Dedup keys on the canonical form, but the *original* path is what's handed
+    /// to the indexer, since [`crate::indexer::DatabaseBackedIndexer::is_inside_workspace`]
+    /// does a string-prefix match against the non-canonicalized workspace root.
+    pending: Arc<Mutex<HashMap<PathBuf, PathBuf>>>,
+    /// `(canonical key, original path)` of whatever's currently being processed, if anything.
+    in_flight: Arc<Mutex<Option<(PathBuf, PathBuf)>>>,
+    doorbell: Arc<Notify>,
+    events: broadcast::Sender<QueueEvent>,
+    worker: JoinHandle<()>,
+    _indexer: PhantomData<I>,
+}
+
+impl<I> IndexQueue<I>
+where
+    I: Indexer + Send + Sync + 'static,
+{
+    /// Spawn a new queue, and its background draining worker, for an existing indexer.
+    pub(crate) fn new(indexer: Arc<Mutex<I>>) -> Self {
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        let in_flight = Arc::new(Mutex::new(None));
+        let doorbell = Arc::new(Notify::new());
+        let (events, _) = broadcast::channel(QUEUE_EVENT_CAPACITY);
+
+        let worker = tokio::spawn(Self::drain(
+            indexer,
+            Arc::clone(&pending),
+            Arc::clone(&in_flight),
+            Arc::clone(&doorbell),
+            events.clone(),
+        ));
+
+        Self {
+            pending,
+            in_flight,
+            doorbell,
+            events,
+            worker,
+            _indexer: PhantomData,
+        }
+    }
+
+    /// Enqueue a path for indexing (or de-indexing, if it turns out to no longer exist on disk
+    /// once the worker gets to it).
+    ///
+    /// If the path is already waiting to be drained, this is a no-op beyond publishing another
+    /// [`QueueEvent::Queued`] - the two changes coalesce into a single re-index.
+    pub(crate) async fn enqueue(&self, path: PathBuf) {
+        let key = canonical_key(&path);
+
+        self.pending.lock().await.insert(key, path.clone());
+
+        let _ = self.events.send(QueueEvent::Queued(path));
+
+        self.doorbell.notify_one();
+    }
+
+    /// The number of paths still waiting to be drained, plus the one (if any) currently being
+    /// processed.
+    pub(crate) async fn pending_count(&self) -> usize {
+        let queued = self.pending.lock().await.len();
+        let in_flight = usize::from(self.in_flight.lock().await.is_some());
+
+        queued + in_flight
+    }
+
+    /// Whether a given path is currently queued, or actively being (de-)indexed.
+    pub(crate) async fn is_indexing(&self, path: &Path) -> bool {
+        let key = canonical_key(path);
+
+        if self.pending.lock().await.contains_key(&key) {
+            return true;
+        }
+
+        self.in_flight.lock().await.as_ref().is_some_and(|(k, _)| *k == key)
+    }
+
+    /// Subscribe to real-time [`QueueEvent`]s as paths move through the queue.
+    pub(crate) fn subscribe(&self) -> QueueSubscription {
+        QueueSubscription {
+            stream: BroadcastStream::new(self.events.subscribe()),
+        }
+    }
+
+    /// Drain the queue, one path at a time, until the [`IndexQueue`] (and every clone of its
+    /// channels) is dropped.
+    ///
+    /// Indexing is performed directly against `indexer`, without taking a permit or otherwise
+    /// bounding concurrency, since only one path is ever drained at a time - this queue exists
+    /// to decouple *when* a path is indexed from the debouncer callback that reported it
+    /// changed, not to parallelize indexing itself (see
+    /// [`crate::indexer::DatabaseBackedIndexer::index_workspace_parallel`] for that).
+    async fn drain(
+        indexer: Arc<Mutex<I>>,
+        pending: Arc<Mutex<HashMap<PathBuf, PathBuf>>>,
+        in_flight: Arc<Mutex<Option<(PathBuf, PathBuf)>>>,
+        doorbell: Arc<Notify>,
+        events: broadcast::Sender<QueueEvent>,
+    ) {
+        loop {
+            let (key, path) = loop {
+                if let Some((key, path)) = pending
+                    .lock()
+                    .await
+                    .iter()
+                    .next()
+                    .map(|(key, path)| (key.clone(), path.clone()))
+                {
+                    break (key, path);
+                }
+
+                doorbell.notified().await;
+            };
+
+            pending.lock().await.remove(&key);
+            *in_flight.lock().await = Some((key, path.clone()));
+
+            let event = match Self::process(&indexer, &path).await {
+                Ok(Some(Operation::Index)) => Some(QueueEvent::Indexed(path)),
+                Ok(Some(Operation::Deindex)) => Some(QueueEvent::Deindexed(path)),
+                Ok(None) => None,
+                Err(e) => {
+                    log::error!("Error processing queued path {}: {e:?}", path.display());
+
+                    Some(QueueEvent::Failed {
+                        path,
+                        error: Arc::new(e),
+                    })
+                }
+            };
+
+            *in_flight.lock().await = None;
+
+            if let Some(event) = event {
+                let _ = events.send(event);
+            }
+        }
+    }
+
+    /// Index, or de-index, a single path, mirroring the rules [`super::Watcher::on_event`] used
+    /// to apply inline before this queue existed.
+    ///
+    /// Returns `Ok(None)` for a path which is neither an existing file nor a deletion (e.g. a
+    /// directory), since there's nothing to do.
+    async fn process(indexer: &Mutex<I>, path: &Path) -> watcher::Result<Option<Operation>> {
+        if path.exists() && path.is_file() {
+            indexer
+                .lock()
+                .await
+                .index(path)
+                .await
+                .map_err(watcher::Error::IndexingFailed)?;
+
+            return Ok(Some(Operation::Index));
+        }
+
+        if !path.exists() {
+            indexer
+                .lock()
+                .await
+                .deindex(path)
+                .await
+                .map_err(watcher::Error::DeindexingFailed)?;
+
+            return Ok(Some(Operation::Deindex));
+        }
+
+        Ok(None)
+    }
+}
+
+impl<I> Drop for IndexQueue<I>
+where
+    I: Indexer + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        // Unlike the debouncer (see `Watcher::stop`), nothing ever closes the channel the
+        // worker is waiting on, so it has to be torn down explicitly rather than left to wind
+        // down on its own.
+        self.worker.abort();
+    }
+}
+
+/// A [`Stream`] of [`QueueEvent`]s from an [`IndexQueue`], returned by
+/// [`IndexQueue::subscribe`]/[`super::Watcher::subscribe`].
+///
+/// A subscriber which falls far enough behind the queue's throughput to overflow the channel's
+/// buffer has the resulting gap logged and skipped transparently, rather than surfaced as an
+/// error.
+#[derive(Debug)]
+pub struct QueueSubscription {
+    stream: BroadcastStream<QueueEvent>,
+}
+
+impl Stream for QueueSubscription {
+    type Item = QueueEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            return match Pin::new(&mut self.stream).poll_next(cx) {
+                Poll::Ready(Some(Ok(event))) => Poll::Ready(Some(event)),
+                Poll::Ready(Some(Err(BroadcastStreamRecvError::Lagged(skipped)))) => {
+                    log::warn!("Index queue subscriber missed {skipped} event(s) while catching up");
+
+                    continue;
+                }
+                Poll::Ready(None) => Poll::Ready(None),
+                Poll::Pending => Poll::Pending,
+            };
+        }
+    }
+}
+
+/// The key a path is deduplicated by in the queue - its canonicalized form, or the raw path
+/// itself if it can no longer be canonicalized (e.g. because it was just deleted).
+fn canonical_key(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}